@@ -0,0 +1,209 @@
+//! Benchmarks for the render path, run on every mode change: `cargo bench -p zjstatus-hints-core`.
+//!
+//! The keymaps hold the bindings the hints look for plus a few hundred unrelated ones, as in a
+//! config with many custom bindings, since every hint scans the whole keymap of its mode.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+use zjstatus_hints_core::ansi::truncate_ansi_string;
+use zjstatus_hints_core::hints::style_key_with_modifier;
+use zjstatus_hints_core::{format_line, render_hints_for_mode, HintContext, LineOptions};
+use zjstatus_hints_core::{KeyOptions, RenderOptions, ALL_MODES};
+
+type Keymap = Vec<(KeyWithModifier, Vec<Action>)>;
+
+const TO_NORMAL: Action = Action::SwitchToMode(InputMode::Normal);
+
+fn char_key(c: char) -> KeyWithModifier {
+    KeyWithModifier::new(BareKey::Char(c))
+}
+
+/// Bindings every mode shares: Ctrl keys into the other modes, and Alt shortcuts.
+fn shared_bindings() -> Keymap {
+    let mut keymap: Keymap = [
+        ('g', InputMode::Locked),
+        ('p', InputMode::Pane),
+        ('t', InputMode::Tab),
+        ('n', InputMode::Resize),
+        ('h', InputMode::Move),
+        ('s', InputMode::Scroll),
+        ('o', InputMode::Session),
+        ('b', InputMode::Tmux),
+    ]
+    .into_iter()
+    .map(|(c, mode)| {
+        (
+            char_key(c).with_ctrl_modifier(),
+            vec![Action::SwitchToMode(mode)],
+        )
+    })
+    .collect();
+    keymap.push((char_key('q').with_ctrl_modifier(), vec![Action::Quit]));
+    keymap.push((
+        char_key('n').with_alt_modifier(),
+        vec![Action::NewPane(None, None, false)],
+    ));
+    keymap.push((
+        char_key('f').with_alt_modifier(),
+        vec![Action::ToggleFloatingPanes],
+    ));
+    for (c, direction) in [
+        ('h', Direction::Left),
+        ('j', Direction::Down),
+        ('k', Direction::Up),
+        ('l', Direction::Right),
+    ] {
+        keymap.push((
+            char_key(c).with_alt_modifier(),
+            vec![Action::MoveFocus(direction)],
+        ));
+    }
+    keymap
+}
+
+/// A few hundred custom bindings none of the hints are about.
+fn filler_bindings() -> Keymap {
+    ('a'..='z')
+        .chain('0'..='9')
+        .flat_map(|c| {
+            [
+                char_key(c).with_ctrl_modifier().with_alt_modifier(),
+                char_key(c).with_super_modifier(),
+                char_key(c).with_super_modifier().with_shift_modifier(),
+                char_key(c).with_ctrl_modifier().with_super_modifier(),
+                char_key(c)
+                    .with_ctrl_modifier()
+                    .with_alt_modifier()
+                    .with_shift_modifier(),
+                char_key(c).with_alt_modifier().with_super_modifier(),
+            ]
+        })
+        .enumerate()
+        .map(|(i, key)| (key, vec![Action::WriteChars(format!("snippet {}", i))]))
+        .collect()
+}
+
+fn keymap_for_mode(mode: InputMode) -> Keymap {
+    let mut keymap = match mode {
+        InputMode::Pane => vec![
+            (
+                char_key('n'),
+                vec![Action::NewPane(None, None, false), TO_NORMAL],
+            ),
+            (char_key('x'), vec![Action::CloseFocus, TO_NORMAL]),
+            (
+                char_key('f'),
+                vec![Action::ToggleFocusFullscreen, TO_NORMAL],
+            ),
+            (char_key('w'), vec![Action::ToggleFloatingPanes, TO_NORMAL]),
+            (
+                char_key('r'),
+                vec![
+                    Action::NewPane(Some(Direction::Right), None, false),
+                    TO_NORMAL,
+                ],
+            ),
+            (
+                char_key('d'),
+                vec![
+                    Action::NewPane(Some(Direction::Down), None, false),
+                    TO_NORMAL,
+                ],
+            ),
+            (char_key('h'), vec![Action::MoveFocus(Direction::Left)]),
+            (char_key('j'), vec![Action::MoveFocus(Direction::Down)]),
+            (char_key('k'), vec![Action::MoveFocus(Direction::Up)]),
+            (char_key('l'), vec![Action::MoveFocus(Direction::Right)]),
+            (KeyWithModifier::new(BareKey::Enter), vec![TO_NORMAL]),
+            (KeyWithModifier::new(BareKey::Esc), vec![TO_NORMAL]),
+        ],
+        InputMode::Normal => vec![],
+        _ => vec![
+            (KeyWithModifier::new(BareKey::Enter), vec![TO_NORMAL]),
+            (KeyWithModifier::new(BareKey::Esc), vec![TO_NORMAL]),
+        ],
+    };
+    keymap.extend(shared_bindings());
+    keymap.extend(filler_bindings());
+    keymap
+}
+
+fn bench_render_hints_for_mode(c: &mut Criterion) {
+    let colors = Styling::default();
+    let options = RenderOptions::default();
+    let context = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+    for mode in [InputMode::Normal, InputMode::Pane, InputMode::Scroll] {
+        let keymap = keymap_for_mode(mode);
+        c.bench_function(&format!("render_hints_for_mode/{:?}", mode), |b| {
+            b.iter(|| {
+                render_hints_for_mode(
+                    black_box(mode),
+                    black_box(&keymap),
+                    &colors,
+                    &options,
+                    &context,
+                )
+            })
+        });
+    }
+    let keymaps: Vec<(InputMode, Keymap)> = ALL_MODES
+        .into_iter()
+        .map(|mode| (mode, keymap_for_mode(mode)))
+        .collect();
+    c.bench_function("render_hints_for_mode/all_modes", |b| {
+        b.iter(|| {
+            for (mode, keymap) in &keymaps {
+                black_box(render_hints_for_mode(
+                    *mode, keymap, &colors, &options, &context,
+                ));
+            }
+        })
+    });
+}
+
+fn bench_style_key_with_modifier(c: &mut Criterion) {
+    let colors = Styling::default();
+    let key_options = KeyOptions::default();
+    let single = [char_key('n')];
+    let group = [
+        char_key('h').with_alt_modifier(),
+        char_key('j').with_alt_modifier(),
+        char_key('k').with_alt_modifier(),
+        char_key('l').with_alt_modifier(),
+        KeyWithModifier::new(BareKey::Left).with_alt_modifier(),
+        KeyWithModifier::new(BareKey::Down).with_alt_modifier(),
+        KeyWithModifier::new(BareKey::Up).with_alt_modifier(),
+        KeyWithModifier::new(BareKey::Right).with_alt_modifier(),
+    ];
+    c.bench_function("style_key_with_modifier/single", |b| {
+        b.iter(|| style_key_with_modifier(black_box(&single), &colors, &key_options))
+    });
+    c.bench_function("style_key_with_modifier/group", |b| {
+        b.iter(|| style_key_with_modifier(black_box(&group), &colors, &key_options))
+    });
+}
+
+fn bench_truncate_ansi_string(c: &mut Criterion) {
+    let keymap = keymap_for_mode(InputMode::Pane);
+    let context = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+    let parts = render_hints_for_mode(
+        InputMode::Pane,
+        &keymap,
+        &Styling::default(),
+        &RenderOptions::default(),
+        &context,
+    );
+    let line = format_line(&parts, &LineOptions::default());
+    c.bench_function("truncate_ansi_string", |b| {
+        b.iter(|| truncate_ansi_string(black_box(&line), "...", 40))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_render_hints_for_mode,
+    bench_style_key_with_modifier,
+    bench_truncate_ansi_string
+);
+criterion_main!(benches);
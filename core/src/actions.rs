@@ -0,0 +1,79 @@
+//! Actions written as in zellij's keybinds, e.g. `NewPane "Right"; SwitchToMode "Normal"`,
+//! read by zellij's own config parser.
+
+use kdl::{KdlDocument, KdlNode};
+use zellij_tile::prelude::actions::Action;
+use zellij_utils::input::options::Options;
+
+/// The actions of a keybind's body, e.g. `CloseFocus; SwitchToMode "Normal"`.
+pub fn parse_actions(src: &str) -> Result<Vec<Action>, String> {
+    parse_document(src)?
+        .nodes()
+        .iter()
+        .map(parse_action)
+        .collect()
+}
+
+fn parse_document(src: &str) -> Result<KdlDocument, String> {
+    src.parse()
+        .map_err(|error: kdl::KdlError| error.to_string())
+}
+
+/// An action to look for in the keymap: a specific one, or any with a given name.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionPattern {
+    /// Boxed, as an `Action` is many times the size of a name.
+    Action(Box<Action>),
+    /// Written with a `"*"` argument, e.g. `NewPane "*"` for a new pane in any direction.
+    Any(String),
+}
+
+impl ActionPattern {
+    pub fn matches(&self, action: &Action) -> bool {
+        match self {
+            ActionPattern::Action(pattern) => action.shallow_eq(pattern),
+            ActionPattern::Any(name) => variant_name(action) == *name,
+        }
+    }
+}
+
+/// Like `parse_actions`, but also accepting wildcards.
+pub fn parse_action_patterns(src: &str) -> Result<Vec<ActionPattern>, String> {
+    parse_document(src)?
+        .nodes()
+        .iter()
+        .map(parse_action_pattern)
+        .collect()
+}
+
+/// Like `parse_action`, but also accepting a wildcard.
+pub fn parse_action_pattern(node: &KdlNode) -> Result<ActionPattern, String> {
+    let wildcard = match node.entries() {
+        [entry] => entry.name().is_none() && entry.value().as_string() == Some("*"),
+        _ => false,
+    };
+    if !wildcard {
+        return parse_action(node).map(|action| ActionPattern::Action(Box::new(action)));
+    }
+    // Go by the name of the action itself where it differs from the keybind's
+    let bare = KdlNode::new(node.name().clone());
+    let name = match parse_action(&bare) {
+        Ok(action) => variant_name(&action),
+        Err(_) => node.name().value().to_string(),
+    };
+    Ok(ActionPattern::Any(name))
+}
+
+/// The name of an action without its arguments, e.g. "NewPane".
+pub fn variant_name(action: &Action) -> String {
+    let debug = format!("{:?}", action);
+    match debug.find(['(', ' ', '{']) {
+        Some(end) => debug[..end].to_string(),
+        None => debug,
+    }
+}
+
+/// The action a keybind node stands for, e.g. `NewPane "Right"`, as zellij reads it.
+pub fn parse_action(node: &KdlNode) -> Result<Action, String> {
+    Action::try_from((node, &Options::default())).map_err(|error| error.to_string())
+}
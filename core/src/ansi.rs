@@ -0,0 +1,213 @@
+//! Splitting rendered output into escape sequences and visible characters, so it can be
+//! measured and truncated without cutting an escape sequence in half.
+//!
+//! Besides the SGR sequences the plugin emits itself, this copes with whatever else may end up
+//! in a label: other CSI sequences, OSC/DCS strings, their 8-bit C1 forms, and sequences cut
+//! off by the end of the input. Anything that isn't printable counts as zero width; the rest
+//! is measured in terminal columns, so wide characters such as emoji count twice.
+
+use unicode_width::UnicodeWidthChar;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+/// 8-bit forms of the sequence introducers.
+const C1_DCS: char = '\u{90}';
+const C1_SOS: char = '\u{98}';
+const C1_CSI: char = '\u{9b}';
+const C1_ST: char = '\u{9c}';
+const C1_OSC: char = '\u{9d}';
+const C1_PM: char = '\u{9e}';
+const C1_APC: char = '\u{9f}';
+
+pub struct AnsiParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> AnsiParser<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+        }
+    }
+
+    pub fn next_segment(&mut self) -> Option<AnsiSegment> {
+        let ch = self.chars.next()?;
+        let mut seq = String::from(ch);
+
+        match ch {
+            ESC => self.escape(&mut seq),
+            C1_CSI => self.csi(&mut seq),
+            C1_OSC | C1_DCS | C1_SOS | C1_PM | C1_APC => self.string(&mut seq),
+            ch if ch.is_control() => {}
+            ch => return Some(AnsiSegment::VisibleChar(ch)),
+        }
+        Some(AnsiSegment::EscapeSequence(seq))
+    }
+
+    fn next_if(&mut self, pred: impl FnOnce(char) -> bool) -> Option<char> {
+        self.chars.next_if(|&ch| pred(ch))
+    }
+
+    fn escape(&mut self, seq: &mut String) {
+        let Some(ch) = self.next_if(|ch| ('\x20'..='\x7e').contains(&ch)) else {
+            // A lone ESC, or one followed by something that can't continue a sequence.
+            return;
+        };
+        seq.push(ch);
+        match ch {
+            '[' => self.csi(seq),
+            ']' | 'P' | 'X' | '^' | '_' => self.string(seq),
+            // nF sequences: intermediates followed by a single final byte.
+            '\x20'..='\x2f' => {
+                while let Some(ch) = self.next_if(|ch| ('\x20'..='\x2f').contains(&ch)) {
+                    seq.push(ch);
+                }
+                if let Some(ch) = self.next_if(|ch| ('\x30'..='\x7e').contains(&ch)) {
+                    seq.push(ch);
+                }
+            }
+            // Everything else is a complete two-character sequence.
+            _ => {}
+        }
+    }
+
+    /// Parameter and intermediate bytes up to a final byte. A character that can't appear in a
+    /// CSI sequence ends it early and is left for the next segment.
+    fn csi(&mut self, seq: &mut String) {
+        while let Some(ch) = self.next_if(|ch| ('\x20'..='\x3f').contains(&ch)) {
+            seq.push(ch);
+        }
+        if let Some(ch) = self.next_if(|ch| ('\x40'..='\x7e').contains(&ch)) {
+            seq.push(ch);
+        }
+    }
+
+    /// OSC, DCS and friends run until BEL or a string terminator (ESC \ or its C1 form). An
+    /// unterminated string swallows the rest of the input, as it would in a terminal.
+    fn string(&mut self, seq: &mut String) {
+        while let Some(ch) = self.chars.next() {
+            seq.push(ch);
+            match ch {
+                BEL | C1_ST => return,
+                ESC => {
+                    if let Some(ch) = self.next_if(|ch| ch == '\\') {
+                        seq.push(ch);
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+pub enum AnsiSegment {
+    EscapeSequence(String),
+    VisibleChar(char),
+}
+
+pub fn calculate_visible_length(text: &str) -> usize {
+    let mut parser = AnsiParser::new(text);
+    let mut len = 0;
+
+    while let Some(segment) = parser.next_segment() {
+        if let AnsiSegment::VisibleChar(ch) = segment {
+            len += ch.width().unwrap_or(0);
+        }
+    }
+
+    len
+}
+
+/// The visible characters of `text`, without any escape sequences or control characters.
+pub fn strip_ansi(text: &str) -> String {
+    let mut parser = AnsiParser::new(text);
+    let mut visible = String::new();
+    while let Some(segment) = parser.next_segment() {
+        if let AnsiSegment::VisibleChar(ch) = segment {
+            visible.push(ch);
+        }
+    }
+    visible
+}
+
+pub fn truncate_ansi_string(text: &str, overflow_str: &str, max_len: usize) -> String {
+    let visible_len = calculate_visible_length(text);
+    let overflow_len = calculate_visible_length(overflow_str);
+
+    if visible_len <= max_len {
+        return text.to_string();
+    }
+
+    if max_len <= overflow_len {
+        return overflow_str.to_string();
+    }
+
+    let target_len = max_len - overflow_len;
+    let mut result = String::new();
+    let mut visible_count = 0;
+    let mut cut = false;
+    let mut parser = AnsiParser::new(text);
+
+    // Escape sequences past the cut are kept, so styles are reset before the overflow string
+    while let Some(segment) = parser.next_segment() {
+        match segment {
+            AnsiSegment::EscapeSequence(seq) => {
+                result.push_str(&seq);
+            }
+            AnsiSegment::VisibleChar(ch) => {
+                let width = ch.width().unwrap_or(0);
+                cut = cut || visible_count + width > target_len;
+                if !cut {
+                    result.push(ch);
+                    visible_count += width;
+                }
+            }
+        }
+    }
+
+    result.push_str(overflow_str);
+    result
+}
+
+/// Like `truncate_ansi_string`, but cuts `text` at the start and puts `overflow_str` before the
+/// end that's kept.
+pub fn truncate_ansi_string_start(text: &str, overflow_str: &str, max_len: usize) -> String {
+    let visible_len = calculate_visible_length(text);
+    let overflow_len = calculate_visible_length(overflow_str);
+
+    if visible_len <= max_len {
+        return text.to_string();
+    }
+
+    if max_len <= overflow_len {
+        return overflow_str.to_string();
+    }
+
+    let kept = max_len - overflow_len;
+    format!("{}{}", overflow_str, skip_columns(text, visible_len - kept))
+}
+
+/// `text` without its first `columns` visible columns, for scrolling it horizontally. Escape
+/// sequences are kept so the rest stays styled; a wide character cut in half becomes a space.
+pub fn skip_columns(text: &str, columns: usize) -> String {
+    let mut result = String::new();
+    let mut skipped = 0;
+    let mut parser = AnsiParser::new(text);
+
+    while let Some(segment) = parser.next_segment() {
+        match segment {
+            AnsiSegment::EscapeSequence(seq) => {
+                result.push_str(&seq);
+            }
+            AnsiSegment::VisibleChar(ch) if skipped < columns => {
+                skipped += ch.width().unwrap_or(0);
+                if skipped > columns {
+                    result.push_str(&" ".repeat(skipped - columns));
+                }
+            }
+            AnsiSegment::VisibleChar(ch) => result.push(ch),
+        }
+    }
+    result
+}
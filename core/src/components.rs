@@ -0,0 +1,62 @@
+//! Hints built from zellij's own UI components, which zellij draws with the current theme.
+//!
+//! Zellij only understands the serialized components in a plugin's own pane, so this suits
+//! running the plugin as a standalone bar; zjstatus still needs the ANSI output.
+
+use crate::hints::{format_key_display, format_modifier_string, get_key_separator, ModeHints};
+use crate::keys::get_common_modifiers;
+use crate::options::KeyOptions;
+use zellij_tile::prelude::*;
+
+/// One ribbon per hint, e.g. "<n> new" with the keys emphasized. Hints that share a modifier
+/// (Normal mode's) are preceded by a ribbon naming it.
+pub fn hints_to_ribbons(mode_hints: &ModeHints, key_options: &KeyOptions) -> Vec<Text> {
+    let mut ribbons = vec![];
+    let common_modifiers = if mode_hints.adjacent {
+        let all_keys: Vec<&KeyWithModifier> = mode_hints
+            .hints
+            .iter()
+            .flat_map(|hint| hint.keys.iter())
+            .collect();
+        get_common_modifiers(all_keys)
+    } else {
+        vec![]
+    };
+    if !common_modifiers.is_empty() {
+        let modifier = format_modifier_string(&common_modifiers, key_options);
+        let len = modifier.chars().count();
+        ribbons.push(Text::new(modifier).color_range(0, 0..len));
+    }
+
+    for hint in &mode_hints.hints {
+        if hint.keys.is_empty() {
+            ribbons.push(Text::new(&hint.label));
+            continue;
+        }
+        let keys = key_label(&hint.keys, &common_modifiers, key_options);
+        let keys_len = keys.chars().count();
+        ribbons.push(Text::new(format!("{} {}", keys, hint.label)).color_range(0, 0..keys_len));
+    }
+    ribbons
+}
+
+/// The keys of a hint as plain text: "<Alt-h|j|k|l>", leaving out modifiers already shown.
+pub fn key_label(
+    keys: &[KeyWithModifier],
+    shown_modifiers: &[KeyModifier],
+    key_options: &KeyOptions,
+) -> String {
+    let hint_modifiers = if shown_modifiers.is_empty() {
+        get_common_modifiers(keys.iter().collect())
+    } else {
+        shown_modifiers.to_vec()
+    };
+    let key_display = format_key_display(keys, &hint_modifiers, key_options);
+    let separator = get_key_separator(&key_display);
+    let modifier = if shown_modifiers.is_empty() && !hint_modifiers.is_empty() {
+        format!("{}-", format_modifier_string(&hint_modifiers, key_options))
+    } else {
+        String::new()
+    };
+    format!("<{}{}>", modifier, key_display.join(separator))
+}
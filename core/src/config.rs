@@ -0,0 +1,402 @@
+//! Reads the plugin's configuration, the key-value pairs of its block in a zellij layout or
+//! config, into the options of the hint engine. The plugin and the preview both go through here,
+//! so the preview renders with the settings the plugin would use. Invalid values are reported on
+//! stderr, which zellij writes to its log, and the defaults used instead.
+
+use crate::actions::parse_action_patterns;
+use crate::keys::parse_key;
+use crate::layout::parse_layout;
+use crate::mode::{mode_name, parse_mode, ALL_MODES};
+use crate::options::{
+    ActionMatch, Align, BaseModeDisplay, ContextHints, CustomHint, HighlightMode, HintStyle,
+    IconTheme, KeyCaps, KeyNames, KeyOptions, KeyOrder, KeysPerAction, Labels, LineOptions,
+    ModifierOrder, Modifiers, OverflowPosition, Profile, RenderOptions, SuperLabel, TextAttributes,
+};
+use crate::preset::Preset;
+use crate::schema::default_flag;
+use crate::style::Colour;
+use crate::theme::Theme;
+use std::collections::{BTreeMap, HashMap};
+use zellij_tile::prelude::*;
+
+/// The preset picked with `style`, which the other options start from.
+pub fn preset(configuration: &BTreeMap<String, String>) -> Preset {
+    configuration
+        .get("style")
+        .and_then(|s| Preset::parse(s))
+        .unwrap_or_default()
+}
+
+/// Reversed hints put the first ones at the end, so that's what truncation keeps.
+fn reverse(configuration: &BTreeMap<String, String>, preset: &Preset) -> bool {
+    configuration
+        .get("reverse")
+        .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+        .unwrap_or(preset.render.reverse)
+}
+
+/// How the line is truncated, padded and aligned.
+pub fn line_options(configuration: &BTreeMap<String, String>) -> LineOptions {
+    let preset = preset(configuration);
+    let reverse = reverse(configuration, &preset);
+    let line_defaults = preset.line;
+    LineOptions {
+        max_length: configuration
+            .get("max_length")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(line_defaults.max_length),
+        overflow_str: configuration
+            .get("overflow_str")
+            .cloned()
+            .unwrap_or(line_defaults.overflow_str),
+        mode_overflow_str: ALL_MODES
+            .into_iter()
+            .filter_map(|mode| {
+                configuration
+                    .get(&format!("overflow_str_{}", mode_name(mode)))
+                    .map(|overflow_str| (mode, overflow_str.clone()))
+            })
+            .collect(),
+        overflow_style: {
+            let mut style = line_defaults.overflow_style;
+            style.foreground = configuration
+                .get("overflow_color")
+                .and_then(|s| Colour::parse(s))
+                .or(style.foreground);
+            style.is_bold = configuration
+                .get("overflow_bold")
+                .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+                .unwrap_or(style.is_bold);
+            style
+        },
+        overflow_position: configuration
+            .get("overflow_position")
+            .and_then(|s| OverflowPosition::parse(s))
+            .unwrap_or(line_defaults.overflow_position),
+        keep_end: reverse,
+        paging: configuration
+            .get("paging")
+            .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+            .unwrap_or(line_defaults.paging),
+        padding_left: configuration
+            .get("padding_left")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(line_defaults.padding_left),
+        padding_right: configuration
+            .get("padding_right")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(line_defaults.padding_right),
+        min_length: configuration
+            .get("min_length")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(line_defaults.min_length),
+        align: configuration
+            .get("align")
+            .and_then(|s| Align::parse(s))
+            .unwrap_or(line_defaults.align),
+    }
+}
+
+/// Which hints are shown and how they're drawn, including the `<mode>_hide_<hint>` and
+/// `<mode>_hint_<label>` families.
+pub fn render_options(configuration: &BTreeMap<String, String>) -> RenderOptions {
+    let preset = preset(configuration);
+    let reverse = reverse(configuration, &preset);
+    let key_options = KeyOptions {
+        per_action: configuration
+            .get("keys_per_action")
+            .and_then(|s| KeysPerAction::parse(s))
+            .unwrap_or(preset.render.keys.per_action),
+        order: configuration
+            .get("key_order")
+            .and_then(|s| KeyOrder::parse(s))
+            .unwrap_or(preset.render.keys.order),
+        modifier_order: configuration
+            .get("modifier_order")
+            .and_then(|s| ModifierOrder::parse(s))
+            .unwrap_or(preset.render.keys.modifier_order),
+        super_label: configuration
+            .get("super_label")
+            .and_then(|s| SuperLabel::parse(s))
+            .unwrap_or(preset.render.keys.super_label),
+        key_names: match configuration.get("key_names").map(|s| KeyNames::parse(s)) {
+            Some(Ok(names)) => names,
+            Some(Err(error)) => {
+                eprintln!("zjstatus-hints: key_names: {}", error);
+                preset.render.keys.key_names.clone()
+            }
+            None => preset.render.keys.key_names.clone(),
+        },
+        attributes: text_attributes(configuration, "key"),
+        caps: configuration
+            .get("key_caps")
+            .and_then(|s| KeyCaps::parse(s))
+            .map(|caps| KeyCaps {
+                background: configuration
+                    .get("key_caps_bg")
+                    .and_then(|s| Colour::parse(s)),
+                ..caps
+            }),
+        only_modifiers: configuration
+            .get("only_modifiers")
+            .and_then(|s| Modifiers::parse(s))
+            .or(preset.render.keys.only_modifiers),
+        exclude_modifiers: configuration
+            .get("exclude_modifiers")
+            .and_then(|s| Modifiers::parse(s))
+            .unwrap_or(preset.render.keys.exclude_modifiers),
+    };
+    let mut options = RenderOptions {
+        keys: key_options,
+        show_missing: configuration
+            .get("show_missing")
+            .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+            .unwrap_or(preset.render.show_missing),
+        quick_keys: configuration
+            .get("quick_keys")
+            .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+            .unwrap_or(preset.render.quick_keys),
+        separate_splits: flag(configuration, "separate_splits"),
+        merge_pages: flag(configuration, "merge_pages"),
+        context_hints: configuration
+            .get("context_hints")
+            .and_then(|s| ContextHints::parse(s))
+            .unwrap_or(preset.render.context_hints),
+        profile: configuration
+            .get("profile")
+            .and_then(|s| Profile::parse(s))
+            .unwrap_or(preset.render.profile),
+        labels: configuration
+            .get("labels")
+            .and_then(|s| Labels::parse(s))
+            .unwrap_or(preset.render.labels),
+        icon_theme: configuration
+            .get("icon_theme")
+            .and_then(|s| IconTheme::parse(s))
+            .unwrap_or_default(),
+        strict_mnemonics: flag(configuration, "strict_mnemonics"),
+        locked_show_bindings: flag(configuration, "locked_show_bindings"),
+        ribbons: flag(configuration, "ribbons"),
+        highlight_mode: configuration
+            .get("highlight_mode")
+            .and_then(|s| HighlightMode::parse(s))
+            .unwrap_or_default(),
+        label_attributes: text_attributes(configuration, "label"),
+        separator: configuration
+            .get("separator")
+            .cloned()
+            .unwrap_or(preset.render.separator),
+        max_hints: configuration
+            .get("max_hints")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(preset.render.max_hints),
+        mode_max_hints: ALL_MODES
+            .into_iter()
+            .filter_map(|mode| {
+                configuration
+                    .get(&format!("{}_max_hints", mode_name(mode)))
+                    .and_then(|s| s.parse().ok())
+                    .map(|max_hints| (mode, max_hints))
+            })
+            .collect(),
+        reverse,
+        hidden_hints: ALL_MODES
+            .into_iter()
+            .filter_map(|mode| {
+                configuration
+                    .get(&format!("{}_hide", mode_name(mode)))
+                    .map(|s| (mode, hint_names(s)))
+            })
+            .collect(),
+        custom_hints: Default::default(),
+        relabels: Default::default(),
+        prefixes: mode_texts(configuration, "prefix"),
+        suffixes: mode_texts(configuration, "suffix"),
+        layouts: ALL_MODES
+            .into_iter()
+            .filter_map(|mode| {
+                let key = format!("{}_layout", mode_name(mode));
+                match parse_layout(configuration.get(&key)?) {
+                    Ok(layout) => Some((mode, layout)),
+                    Err(error) => {
+                        eprintln!("zjstatus-hints: {}: {}", key, error);
+                        None
+                    }
+                }
+            })
+            .collect(),
+        hint_order: ALL_MODES
+            .into_iter()
+            .filter_map(|mode| {
+                configuration
+                    .get(&format!("{}_hints", mode_name(mode)))
+                    .map(|s| (mode, hint_names(s)))
+            })
+            .collect(),
+        select_label: configuration.get("select_label").cloned(),
+        select_key: configuration
+            .get("select_key")
+            .and_then(|s| parse_key(s).ok()),
+        locked_indicator: configuration
+            .get("locked_indicator")
+            .filter(|s| !s.is_empty())
+            .cloned(),
+        tmux_indicator: configuration
+            .get("tmux_indicator")
+            .filter(|s| !s.is_empty())
+            .cloned(),
+        sync_indicator: configuration
+            .get("sync_indicator")
+            .map_or(Some("SYNC".to_string()), |s| {
+                (!s.is_empty()).then(|| s.clone())
+            }),
+        floating_indicator: flag(configuration, "floating_indicator"),
+    };
+    for (key, value) in configuration {
+        let Some((mode, name)) = key.split_once("_hide_") else {
+            continue;
+        };
+        let hide = value.to_lowercase().parse::<bool>().unwrap_or(false);
+        if let (Some(mode), true) = (parse_mode(mode), hide) {
+            options
+                .hidden_hints
+                .entry(mode)
+                .or_default()
+                .push(name.to_string());
+        }
+    }
+    for (key, value) in configuration {
+        let Some((mode, name)) = key.split_once("_hint_") else {
+            continue;
+        };
+        const SETTINGS: [&str; 4] = ["_match", "_fg", "_bg", "_bold"];
+        let is_setting = SETTINGS.iter().any(|suffix| name.ends_with(suffix));
+        let Some(mode) = parse_mode(mode).filter(|_| !is_setting) else {
+            continue;
+        };
+        let actions = match parse_action_patterns(value) {
+            Ok(actions) if !actions.is_empty() => actions,
+            Ok(_) => continue,
+            Err(error) => {
+                eprintln!("zjstatus-hints: {}: {}", key, error);
+                continue;
+            }
+        };
+        let matching = configuration
+            .get(&format!("{}_match", key))
+            .and_then(|s| ActionMatch::parse(s))
+            .unwrap_or_default();
+        let style = hint_style(configuration, key);
+        options
+            .custom_hints
+            .entry(mode)
+            .or_default()
+            .push(CustomHint {
+                label: name.replace('_', " "),
+                actions,
+                matching,
+                style,
+            });
+    }
+    options
+}
+
+/// How the base mode shows its hints, per `base_mode_display` or else `hide_in_base_mode`.
+pub fn base_mode_display(configuration: &BTreeMap<String, String>) -> BaseModeDisplay {
+    let hide_in_base_mode = flag(configuration, "hide_in_base_mode");
+    configuration
+        .get("base_mode_display")
+        .and_then(|s| BaseModeDisplay::parse(s))
+        .unwrap_or(if hide_in_base_mode {
+            BaseModeDisplay::Hidden
+        } else {
+            BaseModeDisplay::Full
+        })
+}
+
+/// Whether the hints keep their theme colors, per `colors` or the preset.
+pub fn colors(configuration: &BTreeMap<String, String>) -> bool {
+    configuration
+        .get("colors")
+        .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+        .unwrap_or(preset(configuration).colors)
+}
+
+/// The `theme_<name>` themes by name.
+pub fn themes(configuration: &BTreeMap<String, String>) -> BTreeMap<String, Theme> {
+    configuration
+        .iter()
+        .filter_map(|(key, value)| {
+            let name = key.strip_prefix("theme_")?;
+            match Theme::parse(value) {
+                Ok(theme) => Some((name.to_string(), theme)),
+                Err(error) => {
+                    eprintln!("zjstatus-hints: theme_{}: {}", name, error);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// The bool option `key`, or its default from the schema when it isn't set.
+pub fn flag(configuration: &BTreeMap<String, String>, key: &str) -> bool {
+    configuration
+        .get(key)
+        .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+        .unwrap_or_else(|| default_flag(key))
+}
+
+/// Hint names separated by spaces or commas, as `<mode>_hints` and `<mode>_hide` take them.
+fn hint_names(s: &str) -> Vec<String> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_lowercase())
+        .collect()
+}
+
+/// The `<key>_fg`, `_bg` and `_bold` options, e.g. `pane_hint_frames_fg`.
+fn hint_style(configuration: &BTreeMap<String, String>, key: &str) -> HintStyle {
+    HintStyle {
+        fg: configuration
+            .get(&format!("{}_fg", key))
+            .and_then(|s| Colour::parse(s)),
+        bg: configuration
+            .get(&format!("{}_bg", key))
+            .and_then(|s| Colour::parse(s)),
+        bold: configuration
+            .get(&format!("{}_bold", key))
+            .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+            .unwrap_or(false),
+    }
+}
+
+/// The `<mode>_<name>` texts with their styles, e.g. `tab_suffix` and `tab_suffix_fg`.
+fn mode_texts(
+    configuration: &BTreeMap<String, String>,
+    name: &str,
+) -> HashMap<InputMode, (String, HintStyle)> {
+    ALL_MODES
+        .into_iter()
+        .filter_map(|mode| {
+            let key = format!("{}_{}", mode_name(mode), name);
+            let text = configuration.get(&key)?.clone();
+            Some((mode, (text, hint_style(configuration, &key))))
+        })
+        .collect()
+}
+
+/// The `<prefix>_bold`, `_italic`, `_dim` and `_underline` options, e.g. `label_italic`.
+fn text_attributes(configuration: &BTreeMap<String, String>, prefix: &str) -> TextAttributes {
+    let attribute = |name: &str| {
+        configuration
+            .get(&format!("{}_{}", prefix, name))
+            .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+    };
+    TextAttributes {
+        bold: attribute("bold"),
+        italic: attribute("italic"),
+        dimmed: attribute("dim"),
+        underline: attribute("underline"),
+    }
+}
@@ -0,0 +1,98 @@
+//! Session state used to tell which hints apply and which would currently do nothing.
+
+use crate::mode::mode_name;
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+
+/// Session state used to spot hints that would be no-ops right now.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HintContext {
+    /// The mode the user returns to after an action, Normal unless configured otherwise
+    /// (e.g. Locked for the "unlock-first" preset).
+    pub base_mode: InputMode,
+    /// Set when zellij is told the terminal lacks arrow fonts (its `simplified_ui` option, reported
+    /// as the `arrow_fonts` capability), so hints stick to ASCII.
+    pub simplified_ui: bool,
+    /// The mode the user was in most recently, other than Normal and the base mode.
+    pub last_mode: Option<InputMode>,
+    /// The mode active before the current one, if it changed since the plugin started.
+    pub previous_mode: Option<InputMode>,
+    /// Name of the active tab.
+    pub tab_name: String,
+    /// Whether the active tab sends input to all its panes.
+    pub sync_active: bool,
+    /// Floating panes in the active tab, shown or not.
+    pub floating_pane_count: usize,
+    /// Whether the active tab's floating panes are shown.
+    pub floating_visible: bool,
+    tab_count: usize,
+    tiled_pane_count: usize,
+    /// Whether the focused tiled pane fills the tab.
+    fullscreen: bool,
+}
+
+impl HintContext {
+    pub fn new(base_mode: InputMode, tabs: &[TabInfo], panes: &PaneManifest) -> Self {
+        let active_tab = tabs.iter().find(|tab| tab.active);
+        let active_panes = active_tab
+            .and_then(|tab| panes.panes.get(&tab.position))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let count_panes = |floating: bool| {
+            active_panes
+                .iter()
+                .filter(|pane| {
+                    pane.is_selectable && pane.is_floating == floating && !pane.is_suppressed
+                })
+                .count()
+        };
+        HintContext {
+            base_mode,
+            simplified_ui: false,
+            last_mode: None,
+            previous_mode: None,
+            tab_name: active_tab.map(|tab| tab.name.clone()).unwrap_or_default(),
+            sync_active: active_tab.is_some_and(|tab| tab.is_sync_panes_active),
+            floating_pane_count: count_panes(true),
+            floating_visible: active_tab.is_some_and(|tab| tab.are_floating_panes_visible),
+            tab_count: tabs.len(),
+            tiled_pane_count: count_panes(false),
+            fullscreen: active_panes
+                .iter()
+                .any(|pane| pane.is_focused && pane.is_fullscreen && !pane.is_floating),
+        }
+    }
+
+    /// Fills in the variables of a `format` template: `{hints}`, `{mode}`, `{tab}`, `{sync}`
+    /// ("sync ON" while the active tab is synced) and `{floating}` (the number of floating
+    /// panes in the active tab). Those that don't apply are left empty.
+    pub fn expand(&self, template: &str, mode: InputMode, hints: &str) -> String {
+        let floating = match self.floating_pane_count {
+            0 => String::new(),
+            count => count.to_string(),
+        };
+        template
+            .replace("{mode}", mode_name(mode))
+            .replace("{tab}", &self.tab_name)
+            .replace("{sync}", if self.sync_active { "sync ON" } else { "" })
+            .replace("{floating}", &floating)
+            .replace("{hints}", hints)
+    }
+
+    /// Counts are zero until the first TabUpdate/PaneUpdate, so nothing is a no-op before then.
+    pub fn is_noop(&self, action: &Action) -> bool {
+        match action {
+            Action::CloseTab | Action::GoToNextTab | Action::GoToPreviousTab => self.tab_count == 1,
+            Action::BreakPane
+            | Action::MoveFocus(_)
+            | Action::FocusNextPane
+            | Action::FocusPreviousPane => self.tiled_pane_count == 1,
+            Action::ToggleFocusFullscreen => self.fullscreen,
+            // Nothing to show or hide without floating panes
+            Action::ToggleFloatingPanes => {
+                self.tiled_pane_count > 0 && self.floating_pane_count == 0
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,1201 @@
+//! The built-in hints for each mode and how they are styled.
+
+use crate::actions::variant_name;
+use crate::ansi::calculate_visible_length;
+use crate::components::key_label;
+use crate::context::HintContext;
+use crate::keys::{
+    find_keys_for_action_groups, find_keys_for_actions, find_keys_matching, find_keys_to_mode,
+    get_common_modifiers, get_select_key,
+};
+use crate::layout::LayoutItem;
+use crate::mode::{mode_name, parse_mode, takes_text};
+use crate::options::{
+    hint_name_matches, BaseModeDisplay, ContextHints, HintStyle, KeyOptions, Labels, Profile,
+    RenderOptions,
+};
+use crate::style::{
+    Colour::{Fixed, RGB},
+    Style, StyledString, StyledStrings,
+};
+use crate::table::{group_splits, merge_pages, mode_hints, HintKeys, Priority};
+use std::collections::BTreeMap;
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+use zellij_tile_utils::palette_match;
+
+const RIBBON_ARROW: &str = "\u{e0b0}";
+
+const KEY_PATTERNS_NO_SEPARATOR: &[&str] = &["HJKL", "hjkl", "←↓↑→", "←→", "↓↑", "[]"];
+
+pub type ActionLabel = (Action, &'static str);
+/// A hint of the current mode, with what it was rendered from.
+#[derive(Clone, Debug)]
+pub struct Hint {
+    /// The name it's configured by, see `hint_name`.
+    pub name: String,
+    pub label: String,
+    pub keys: Vec<KeyWithModifier>,
+    pub parts: Vec<StyledString>,
+    /// Drawn as is, without gaps or separators around it, like a layout's `text`.
+    pub decoration: bool,
+    /// How long it's kept when the line is too short, see `fit_hints`.
+    pub priority: Priority,
+}
+
+impl Hint {
+    pub fn new(label: &str, keys: &[KeyWithModifier], parts: Vec<StyledString>) -> Self {
+        Hint {
+            name: hint_name(label),
+            label: label.to_string(),
+            keys: keys.to_vec(),
+            parts,
+            decoration: false,
+            priority: Priority::Normal,
+        }
+    }
+}
+
+/// The hints of a mode, before they're laid out on a line.
+#[derive(Clone, Debug, Default)]
+pub struct ModeHints {
+    /// Drawn before the hints, e.g. the modifier shared by Normal mode's labels.
+    pub prefix: Vec<StyledString>,
+    /// Whether the hints follow each other without gaps, as Normal mode's labels do.
+    pub adjacent: bool,
+    pub hints: Vec<Hint>,
+    /// Drawn after the hints and kept when they're cut, e.g. a mode's configured suffix.
+    pub suffix: Vec<StyledString>,
+}
+
+pub const NORMAL_MODE_ACTIONS: &[ActionLabel] = &[
+    (Action::SwitchToMode(InputMode::Pane), "pane"),
+    (Action::SwitchToMode(InputMode::Tab), "tab"),
+    (Action::SwitchToMode(InputMode::Resize), "n-resize"),
+    (Action::SwitchToMode(InputMode::Move), "h-move"),
+    (Action::SwitchToMode(InputMode::Scroll), "scroll"),
+    (Action::SwitchToMode(InputMode::Search), "search"),
+    (Action::SwitchToMode(InputMode::Session), "o-session"),
+    (Action::Quit, "quit"),
+];
+
+/// Shortcuts usually bound in every mode, listed after Normal mode's switches with `quick_keys`.
+/// Each is a group of actions whose keys share a hint.
+pub const QUICK_KEY_GROUPS: &[(&[&[Action]], &str)] = &[
+    (&[&[Action::NewPane(None, None, false)]], "new"),
+    (
+        &[
+            &[Action::MoveFocusOrTab(Direction::Left)],
+            &[Action::MoveFocus(Direction::Left)],
+            &[Action::MoveFocus(Direction::Down)],
+            &[Action::MoveFocus(Direction::Up)],
+            &[Action::MoveFocusOrTab(Direction::Right)],
+            &[Action::MoveFocus(Direction::Right)],
+        ],
+        "focus",
+    ),
+    (
+        &[
+            &[Action::Resize(Resize::Increase, None)],
+            &[Action::Resize(Resize::Decrease, None)],
+        ],
+        "resize",
+    ),
+    (
+        &[&[Action::PreviousSwapLayout], &[Action::NextSwapLayout]],
+        "layout",
+    ),
+    (&[&[Action::ToggleFloatingPanes]], "float"),
+];
+
+/// Toggles listed last in Normal mode wherever they're bound, as they are in no other mode's way.
+pub const NORMAL_MODE_TOGGLES: &[(&[&[Action]], &str)] = &[
+    (&[&[Action::TogglePaneFrames]], "frames"),
+    (&[&[Action::ToggleMouseMode]], "mouse"),
+];
+
+/// The name a hint is configured by, e.g. `quit` in `normal_hide_quit`: its label without Normal
+/// mode's mnemonic prefix, in snake case, with the symbolic labels spelled out.
+pub fn hint_name(label: &str) -> String {
+    match label {
+        "x" => "close".to_string(),
+        "→" => "split_right".to_string(),
+        "↓" => "split_down".to_string(),
+        "split →↓" => "split".to_string(),
+        "page/½page" => "pages".to_string(),
+        "break ←" => "break_left".to_string(),
+        "break →" => "break_right".to_string(),
+        label => {
+            let label = match label.split_once('-') {
+                Some((prefix, rest)) if prefix.chars().count() == 1 => rest,
+                _ => label,
+            };
+            label.replace(' ', "_")
+        }
+    }
+}
+
+/// Replaces the arrows and marks used in hints with ASCII look-alikes, for terminals without
+/// arrow fonts.
+pub fn ascii_glyphs(text: &str) -> String {
+    text.chars()
+        .map(|ch| match ch {
+            '←' => '<',
+            '→' => '>',
+            '↑' => '^',
+            '↓' => 'v',
+            '✗' => 'x',
+            ch => ch,
+        })
+        .collect()
+}
+
+pub fn format_modifier_string(modifiers: &[KeyModifier], key_options: &KeyOptions) -> String {
+    key_options.modifier_names(modifiers).join("-")
+}
+
+/// Each key as shown after the modifiers they share, e.g. "h" or "Shift h" after "Alt-".
+pub fn format_key_display(
+    key_bindings: &[KeyWithModifier],
+    common_modifiers: &[KeyModifier],
+    key_options: &KeyOptions,
+) -> Vec<String> {
+    key_bindings
+        .iter()
+        .map(|key| {
+            let unique_modifiers: Vec<KeyModifier> = key
+                .key_modifiers
+                .iter()
+                .filter(|m| !common_modifiers.contains(m))
+                .copied()
+                .collect();
+            key_options
+                .modifier_names(&unique_modifiers)
+                .into_iter()
+                .map(str::to_string)
+                .chain(std::iter::once(key_options.key_names.name(&key.bare_key)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+pub fn get_key_separator(key_display: &[String]) -> &'static str {
+    let key_string = key_display.join("");
+    if KEY_PATTERNS_NO_SEPARATOR.contains(&&key_string[..]) {
+        ""
+    } else {
+        "|"
+    }
+}
+
+pub fn style_key_with_modifier(
+    key_bindings: &[KeyWithModifier],
+    palette: &Styling,
+    key_options: &KeyOptions,
+) -> Vec<StyledString> {
+    if key_bindings.is_empty() {
+        return vec![];
+    }
+
+    let saturated_bg = palette_match!(palette.ribbon_unselected.background);
+    let contrasting_fg = palette_match!(palette.ribbon_unselected.base);
+    let mut styled_parts = vec![];
+
+    let common_modifiers = get_common_modifiers(key_bindings.iter().collect());
+    let modifier_str = format_modifier_string(&common_modifiers, key_options);
+    let key_display = format_key_display(key_bindings, &common_modifiers, key_options);
+    let key_separator = get_key_separator(&key_display);
+
+    if !modifier_str.is_empty() {
+        styled_parts.push(
+            key_options
+                .attributes
+                .apply(Style::new().fg(contrasting_fg).on(saturated_bg).bold())
+                .paint(format!(" {}-", modifier_str.to_lowercase())),
+        );
+    } else {
+        styled_parts.push(Style::new().fg(contrasting_fg).on(saturated_bg).paint(" "));
+    }
+
+    for (idx, key) in key_display.iter().enumerate() {
+        if idx > 0 && !key_separator.is_empty() {
+            styled_parts.push(
+                Style::new()
+                    .fg(contrasting_fg)
+                    .on(saturated_bg)
+                    .paint(key_separator),
+            );
+        }
+        styled_parts.push(
+            key_options
+                .attributes
+                .apply(Style::new().fg(contrasting_fg).on(saturated_bg).bold())
+                .paint(key.clone()),
+        );
+    }
+
+    styled_parts.push(Style::new().fg(contrasting_fg).on(saturated_bg).paint(" "));
+
+    if let Some(caps) = key_options.caps {
+        styled_parts.insert(0, caps.paint(caps.left, saturated_bg));
+        styled_parts.push(caps.paint(caps.right, saturated_bg));
+    }
+
+    styled_parts
+}
+
+/// The (char) position of the letter highlighted in a label: that of the hint's first key, or the
+/// first letter (past any icon) when the hint has no keys of its own. When the key's letter isn't
+/// in the label, e.g. after remapping for Colemak or Dvorak, that's the first letter too, unless
+/// `strict` asks for no highlight.
+fn highlight_position(description: &str, keys: &[KeyWithModifier], strict: bool) -> Option<usize> {
+    let first_letter = || {
+        description
+            .chars()
+            .position(char::is_alphanumeric)
+            .or(Some(0))
+    };
+    match keys.first() {
+        Some(key) => {
+            let key_char = format!("{}", key.bare_key).to_lowercase().chars().next()?;
+            let pos = description
+                .chars()
+                .position(|c| c.to_lowercase().next() == Some(key_char));
+            if strict {
+                pos
+            } else {
+                pos.or_else(first_letter)
+            }
+        }
+        None => first_letter(),
+    }
+}
+
+/// The label as configured by `labels`: whole, or cut down to its highlighted letter (its first
+/// letter when the key doesn't appear in it), after its icon from `icon_theme`.
+pub fn shown_label(description: &str, keys: &[KeyWithModifier], options: &RenderOptions) -> String {
+    let label = match options.labels {
+        Labels::Full => description.to_string(),
+        Labels::Minimal => {
+            let pos = highlight_position(description, keys, false).unwrap_or(0);
+            description
+                .chars()
+                .nth(pos)
+                .map(String::from)
+                .unwrap_or_default()
+        }
+    };
+    match options.icon_theme.icon(&hint_name(description)) {
+        Some(icon) => format!("{} {}", icon, label),
+        None => label,
+    }
+}
+
+pub fn style_description(
+    description: &str,
+    palette: &Styling,
+    keys: &[KeyWithModifier],
+    options: &RenderOptions,
+) -> Vec<StyledString> {
+    let less_saturated_bg = palette_match!(palette.text_unselected.background);
+    let contrasting_fg = palette_match!(palette.text_unselected.base);
+    let highlight_fg = palette_match!(palette.ribbon_selected.base);
+    let attributes = options.label_attributes;
+    let label_style = attributes.apply(Style::new().fg(contrasting_fg).on(less_saturated_bg));
+
+    let mut parts = vec![];
+
+    parts.push(
+        Style::new()
+            .fg(contrasting_fg)
+            .on(less_saturated_bg)
+            .paint(" "),
+    );
+
+    if !description.is_empty() {
+        let highlight_pos = highlight_position(description, keys, options.strict_mnemonics);
+
+        if let Some(pos) = highlight_pos {
+            // Split description into parts: before, highlighted char, after
+            let chars: Vec<char> = description.chars().collect();
+
+            if pos > 0 {
+                parts.push(label_style.paint(chars[..pos].iter().collect::<String>()));
+            }
+
+            parts.push(
+                attributes
+                    .apply(Style::new().fg(highlight_fg).on(less_saturated_bg).bold())
+                    .paint(chars[pos].to_string()),
+            );
+
+            if pos + 1 < chars.len() {
+                parts.push(label_style.paint(chars[pos + 1..].iter().collect::<String>()));
+            }
+        } else {
+            // Key letter not in description, just render description normally
+            parts.push(label_style.paint(description.to_string()));
+        }
+    }
+
+    parts.push(
+        Style::new()
+            .fg(contrasting_fg)
+            .on(less_saturated_bg)
+            .paint(" "),
+    );
+
+    parts
+}
+
+/// The key and label of a hint, or its "✗ label" placeholder; empty if it isn't shown at all.
+pub fn style_hint(
+    keys: &[KeyWithModifier],
+    description: &str,
+    colors: &Styling,
+    options: &RenderOptions,
+) -> Vec<StyledString> {
+    let label = shown_label(description, keys, options);
+    if !keys.is_empty() {
+        let mut hint = style_key_with_modifier(keys, colors, &options.keys);
+        hint.extend(style_description(&label, colors, keys, options));
+        hint
+    } else if options.show_missing {
+        style_missing(&label, colors)
+    } else {
+        vec![]
+    }
+}
+
+/// What goes before a hint: a plain space before the first one, the configured separator between
+/// the rest.
+pub fn hint_gap(first: bool, colors: &Styling, options: &RenderOptions) -> StyledString {
+    if first || options.separator.is_empty() {
+        Style::new().paint(" ")
+    } else {
+        let separator_fg = palette_match!(colors.text_unselected.base);
+        Style::new()
+            .fg(separator_fg)
+            .paint(options.separator.clone())
+    }
+}
+
+pub fn add_hint(
+    hints: &mut Vec<Hint>,
+    keys: &[KeyWithModifier],
+    description: &str,
+    colors: &Styling,
+    options: &RenderOptions,
+) {
+    let hint = style_hint(keys, description, colors, options);
+    if !hint.is_empty() {
+        hints.push(Hint::new(description, keys, hint));
+    }
+}
+
+pub fn add_contextual_hint(
+    hints: &mut Vec<Hint>,
+    keys: &[KeyWithModifier],
+    description: &str,
+    colors: &Styling,
+    options: &RenderOptions,
+    is_noop: bool,
+) {
+    match (is_noop, options.context_hints) {
+        (true, ContextHints::Hide) => {}
+        (true, ContextHints::Dim) => {
+            let mut hint = style_hint(keys, description, colors, options);
+            if !hint.is_empty() {
+                for part in &mut hint {
+                    part.style_ref_mut().is_dimmed = true;
+                }
+                hints.push(Hint::new(description, keys, hint));
+            }
+        }
+        _ => add_hint(hints, keys, description, colors, options),
+    }
+}
+
+pub fn style_missing(description: &str, palette: &Styling) -> Vec<StyledString> {
+    let less_saturated_bg = palette_match!(palette.text_unselected.background);
+    let contrasting_fg = palette_match!(palette.text_unselected.base);
+
+    vec![Style::new()
+        .fg(contrasting_fg)
+        .on(less_saturated_bg)
+        .dimmed()
+        .paint(format!(" ✗ {} ", description))]
+}
+
+/// The labels of the custom hints of `mode`, with the keys bound to their actions and their
+/// styles.
+fn custom_hints(
+    mode: InputMode,
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    options: &RenderOptions,
+) -> Vec<(String, Vec<KeyWithModifier>, HintStyle)> {
+    options
+        .custom_hints
+        .get(&mode)
+        .into_iter()
+        .flatten()
+        .map(|hint| {
+            let keys = find_keys_matching(keymap, &hint.actions, hint.matching, &options.keys);
+            (hint.label.clone(), keys, hint.style)
+        })
+        .collect()
+}
+
+/// The hints as declared by a layout: built-in and custom ones picked by name, and the layout's
+/// own key hints, text and badges in between.
+fn apply_layout(
+    mut hints: Vec<Hint>,
+    layout: &[LayoutItem],
+    mode: InputMode,
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    colors: &Styling,
+    options: &RenderOptions,
+) -> Vec<Hint> {
+    let mut laid_out = vec![];
+    for item in layout {
+        match item {
+            LayoutItem::Hint { name, label, style } => {
+                let (matching, rest): (Vec<Hint>, Vec<Hint>) = hints
+                    .into_iter()
+                    .partition(|hint| hint_name_matches(name, &hint.name));
+                hints = rest;
+                for mut hint in matching {
+                    if let Some(label) = label {
+                        let parts = style_entry(mode, &hint.keys, label, colors, options);
+                        hint = Hint {
+                            name: hint.name,
+                            priority: hint.priority,
+                            ..Hint::new(label, &hint.keys, parts)
+                        };
+                    }
+                    style.apply(&mut hint.parts);
+                    laid_out.push(hint);
+                }
+            }
+            LayoutItem::KeyHint(custom) => {
+                let keys =
+                    find_keys_matching(keymap, &custom.actions, custom.matching, &options.keys);
+                let mut parts = style_entry(mode, &keys, &custom.label, colors, options);
+                if !parts.is_empty() {
+                    custom.style.apply(&mut parts);
+                    laid_out.push(Hint::new(&custom.label, &keys, parts));
+                }
+            }
+            LayoutItem::Text { text, style } => laid_out.push(Hint {
+                name: "text".to_string(),
+                decoration: true,
+                ..Hint::new(text, &[], vec![style.paint(text)])
+            }),
+            LayoutItem::ModeBadge => laid_out.push(mode_badge(mode, colors)),
+        }
+    }
+    laid_out
+}
+
+/// A hint's parts as its mode draws them: just the label in Normal mode, where the keys share
+/// the modifier shown up front, and key and label elsewhere.
+fn style_entry(
+    mode: InputMode,
+    keys: &[KeyWithModifier],
+    label: &str,
+    colors: &Styling,
+    options: &RenderOptions,
+) -> Vec<StyledString> {
+    if mode != InputMode::Normal {
+        return style_hint(keys, label, colors, options);
+    }
+    let shown = shown_label(label, &[], options);
+    if !keys.is_empty() {
+        style_description(&shown, colors, &[], options)
+    } else if options.show_missing {
+        style_missing(&shown, colors)
+    } else {
+        vec![]
+    }
+}
+
+/// The raw keymap as zellij sends it, a hint per key, e.g. "Ctrl p: [SwitchToMode(Pane)]", to see
+/// why a hint is missing when the actions bound differ from those it looks for.
+pub fn keymap_hints(keymap: &[(KeyWithModifier, Vec<Action>)]) -> ModeHints {
+    let hints = keymap
+        .iter()
+        .map(|(key, actions)| {
+            let entry = format!("{}: {:?}", key, actions);
+            Hint::new(&entry, &[], vec![Style::new().paint(entry.clone())])
+        })
+        .collect();
+    ModeHints {
+        hints,
+        ..Default::default()
+    }
+}
+
+/// The keys bound in Locked mode besides those leaving it, e.g. a scroll toggle some configs keep
+/// while locked, with a label made from the name of their first action ("scroll up").
+fn locked_bindings(
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    key_options: &KeyOptions,
+) -> Vec<(String, Vec<KeyWithModifier>)> {
+    let mut bindings: Vec<(&[Action], Vec<KeyWithModifier>)> = vec![];
+    for (key, actions) in keymap {
+        let leaves = actions
+            .iter()
+            .any(|action| matches!(action, Action::SwitchToMode(_)));
+        if actions.is_empty() || leaves {
+            continue;
+        }
+        match bindings.iter_mut().find(|(bound, _)| bound == actions) {
+            Some((_, keys)) => keys.push(key.clone()),
+            None => bindings.push((actions, vec![key.clone()])),
+        }
+    }
+    bindings
+        .into_iter()
+        .map(|(actions, keys)| {
+            let mut label = String::new();
+            for c in variant_name(&actions[0]).chars() {
+                if c.is_uppercase() && !label.is_empty() {
+                    label.push(' ');
+                }
+                label.extend(c.to_lowercase());
+            }
+            (label, key_options.apply(keys))
+        })
+        .collect()
+}
+
+/// The name of `mode` in capitals, e.g. "PANE", as a badge in the theme's selected ribbon colors.
+pub fn mode_badge(mode: InputMode, colors: &Styling) -> Hint {
+    let badge_bg = palette_match!(colors.ribbon_selected.background);
+    let badge_fg = palette_match!(colors.ribbon_selected.base);
+    let parts = vec![Style::new()
+        .fg(badge_fg)
+        .on(badge_bg)
+        .bold()
+        .paint(format!(" {} ", mode_name(mode).to_uppercase()))];
+    Hint {
+        name: "mode_badge".to_string(),
+        priority: Priority::High,
+        ..Hint::new(mode_name(mode), &[], parts)
+    }
+}
+
+/// A hint drawn as one of the ribbons of zellij's status bar, e.g. " <n> new " between arrows, in
+/// the selected ribbon colors or the unselected ones. Without `arrows` ribbons are set apart by a
+/// space instead.
+pub fn style_ribbon(
+    keys: &str,
+    label: &str,
+    palette: &Styling,
+    selected: bool,
+    arrows: bool,
+) -> Vec<StyledString> {
+    let ribbon = if selected {
+        palette.ribbon_selected
+    } else {
+        palette.ribbon_unselected
+    };
+    let ribbon_bg = palette_match!(ribbon.background);
+    let text_fg = palette_match!(ribbon.base);
+    let key_fg = palette_match!(ribbon.emphasis_0);
+    let bar_bg = palette_match!(palette.text_unselected.background);
+
+    let mut parts = vec![];
+    if arrows {
+        parts.push(Style::new().fg(bar_bg).on(ribbon_bg).paint(RIBBON_ARROW));
+    }
+    if !keys.is_empty() {
+        parts.push(
+            Style::new()
+                .fg(key_fg)
+                .on(ribbon_bg)
+                .bold()
+                .paint(format!(" {}", keys)),
+        );
+    }
+    parts.push(
+        Style::new()
+            .fg(text_fg)
+            .on(ribbon_bg)
+            .bold()
+            .paint(format!(" {} ", label)),
+    );
+    if arrows {
+        parts.push(Style::new().fg(ribbon_bg).on(bar_bg).paint(RIBBON_ARROW));
+    } else {
+        parts.push(Style::new().paint(" "));
+    }
+    parts
+}
+
+/// `indicator` as a badge in the theme's error color, warning that input goes to every pane.
+pub fn sync_badge(indicator: &str, colors: &Styling) -> Vec<StyledString> {
+    let warning_bg = palette_match!(colors.exit_code_error.base);
+    let contrasting_fg = palette_match!(colors.ribbon_unselected.base);
+    vec![Style::new()
+        .fg(contrasting_fg)
+        .on(warning_bg)
+        .bold()
+        .paint(format!(" {} ", indicator))]
+}
+
+/// The base mode's line as `display` draws it: the mode's hints, dimmed or not, its badge alone,
+/// or nothing. The sync indicator leads the badge, and stands in for hidden hints on its own.
+pub fn base_mode_parts(
+    display: BaseModeDisplay,
+    mode: InputMode,
+    hints: impl FnOnce() -> Vec<StyledString>,
+    sync_indicator: Option<&str>,
+    colors: &Styling,
+) -> Vec<StyledString> {
+    match display {
+        BaseModeDisplay::Full => hints(),
+        BaseModeDisplay::Dimmed => {
+            let mut parts = hints();
+            for part in &mut parts {
+                part.style_ref_mut().is_dimmed = true;
+            }
+            parts
+        }
+        BaseModeDisplay::Badge => {
+            let mut parts = mode_badge(mode, colors).parts;
+            if let Some(indicator) = sync_indicator {
+                parts.splice(0..0, sync_badge(indicator, colors));
+            }
+            parts
+        }
+        BaseModeDisplay::Hidden => match sync_indicator {
+            Some(indicator) => sync_badge(indicator, colors),
+            None => vec![],
+        },
+    }
+}
+
+/// The hint for leaving a mode without hints of its own: other modes return to the base mode,
+/// and leaving the base mode means going to Normal.
+pub fn add_return_hint(
+    hints: &mut Vec<Hint>,
+    mode: InputMode,
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    colors: &Styling,
+    options: &RenderOptions,
+    context: &HintContext,
+) {
+    // Back to the mode this one was entered from first, e.g. Scroll mode from EnterSearch
+    let previous = context
+        .previous_mode
+        .filter(|previous| ![InputMode::Normal, context.base_mode, mode].contains(previous));
+    let targets = if mode == context.base_mode {
+        vec![InputMode::Normal]
+    } else {
+        previous
+            .into_iter()
+            .chain([context.base_mode, InputMode::Normal])
+            .collect()
+    };
+    // Modes taking text show Esc on its own, as "cancel"
+    let cancel = if takes_text(mode) {
+        HintKeys::Cancel.find(keymap, &options.keys)
+    } else {
+        vec![]
+    };
+    let keymap: Vec<_> = keymap
+        .iter()
+        .filter(|(key, _)| !cancel.contains(key))
+        .cloned()
+        .collect();
+    if let Some((target, keys)) = find_keys_to_mode(&keymap, &targets, &options.keys) {
+        let label = match (options.profile, target) {
+            (_, target) if previous == Some(target) => format!("back to {}", mode_name(target)),
+            (Profile::UnlockFirst, InputMode::Locked) => "lock".to_string(),
+            (Profile::UnlockFirst, InputMode::Normal) => "unlock".to_string(),
+            (_, target) => mode_name(target).to_string(),
+        };
+        add_hint(hints, &keys, &label, colors, options);
+        if let Some(hint) = hints.last_mut().filter(|hint| hint.label == label) {
+            hint.priority = Priority::High;
+        }
+    }
+}
+
+/// The hints matching each name of `order` in turn, leaving out those that match none.
+fn order_hints(mut hints: Vec<Hint>, order: &[String]) -> Vec<Hint> {
+    let mut ordered = vec![];
+    for pattern in order {
+        let (matching, rest): (Vec<Hint>, Vec<Hint>) = hints
+            .into_iter()
+            .partition(|hint| hint_name_matches(pattern, &hint.name));
+        ordered.extend(matching);
+        hints = rest;
+    }
+    ordered
+}
+
+pub fn render_hints_for_mode(
+    mode: InputMode,
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    colors: &Styling,
+    options: &RenderOptions,
+    context: &HintContext,
+) -> Vec<StyledString> {
+    let mode_hints = collect_hints_for_mode(mode, keymap, colors, options, context);
+    join_hints(mode_hints, colors, options)
+}
+
+/// Lays the hints out on a line: the prefix, then each hint after its gap or separator.
+pub fn join_hints(
+    mode_hints: ModeHints,
+    colors: &Styling,
+    options: &RenderOptions,
+) -> Vec<StyledString> {
+    let ModeHints {
+        prefix,
+        adjacent,
+        hints,
+        suffix,
+    } = mode_hints;
+    let mut parts = prefix;
+    let mut after_decoration = false;
+    for (idx, hint) in hints.into_iter().enumerate() {
+        if hint.decoration || after_decoration {
+            // Decorations bring their own spacing
+        } else if !adjacent {
+            parts.push(hint_gap(idx == 0, colors, options));
+        } else if idx > 0 && !options.separator.is_empty() {
+            parts.push(hint_gap(false, colors, options));
+        }
+        after_decoration = hint.decoration;
+        parts.extend(hint.parts);
+    }
+    parts.extend(suffix);
+    parts
+}
+
+/// Splits the hints into pages that each fit in `width` columns once joined, keeping the prefix
+/// and suffix on every page. A hint wider than that gets a page of its own.
+pub fn paginate_hints(
+    mode_hints: ModeHints,
+    width: usize,
+    colors: &Styling,
+    options: &RenderOptions,
+) -> Vec<ModeHints> {
+    let ModeHints {
+        prefix,
+        adjacent,
+        hints,
+        suffix,
+    } = mode_hints;
+    let empty_page = || ModeHints {
+        prefix: prefix.clone(),
+        adjacent,
+        hints: vec![],
+        suffix: suffix.clone(),
+    };
+    let mut pages = vec![];
+    let mut page = empty_page();
+    for hint in hints {
+        let mut with_hint = page.clone();
+        with_hint.hints.push(hint.clone());
+        let joined = StyledStrings(&join_hints(with_hint.clone(), colors, options)).to_string();
+        if page.hints.is_empty() || calculate_visible_length(&joined) <= width {
+            page = with_hint;
+        } else {
+            pages.push(std::mem::replace(&mut page, empty_page()));
+            page.hints.push(hint);
+        }
+    }
+    pages.push(page);
+    pages
+}
+
+/// The hints shown in `mode`, after leaving out hidden ones and applying `max_hints`, which
+/// doesn't count a layout's text.
+pub fn collect_hints_for_mode(
+    mode: InputMode,
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    colors: &Styling,
+    options: &RenderOptions,
+    context: &HintContext,
+) -> ModeHints {
+    let scoped;
+    let options = if mode != InputMode::Normal && options.keys.filters_modifiers() {
+        scoped = RenderOptions {
+            keys: options.keys.for_mode(mode),
+            ..options.clone()
+        };
+        &scoped
+    } else {
+        options
+    };
+    // Normal mode's labels follow the modifier they share without gaps
+    let mut prefix = vec![];
+    let mut adjacent = false;
+    let mut hints = vec![];
+    let select_keys = get_select_key(keymap, context.base_mode, options.select_key.as_ref());
+    let select_label = options.select_label.as_deref().unwrap_or("select");
+    let key_options = &options.keys;
+    // The mode switch Normal mode draws in the selected ribbon colors, as a landmark
+    let highlighted = options
+        .highlight_mode
+        .mode(context.last_mode)
+        .filter(|_| mode == InputMode::Normal)
+        .map(mode_name);
+    let is_highlighted = |label: &str| highlighted.is_some_and(|name| hint_name(label) == name);
+    let highlight = HintStyle {
+        fg: Some(palette_match!(colors.ribbon_selected.base)),
+        bg: Some(palette_match!(colors.ribbon_selected.background)),
+        bold: true,
+    };
+
+    match mode {
+        InputMode::Normal => {
+            // Modes named in the configured order that aren't listed already get an entry too
+            let extra_modes = options
+                .hint_order
+                .get(&InputMode::Normal)
+                .into_iter()
+                .flatten()
+                .filter_map(|name| parse_mode(name))
+                .filter(|mode| {
+                    !NORMAL_MODE_ACTIONS.iter().any(|(action, _)| {
+                        matches!(action, Action::SwitchToMode(listed) if listed == mode)
+                    })
+                })
+                .map(|mode| (Action::SwitchToMode(mode), mode_name(mode)));
+            let actions: Vec<ActionLabel> = NORMAL_MODE_ACTIONS
+                .iter()
+                .filter(|(_, label)| options.profile.lists(&hint_name(label)))
+                .cloned()
+                .chain(extra_modes)
+                .collect();
+            let mut entries: Vec<(String, Vec<KeyWithModifier>, HintStyle)> = actions
+                .iter()
+                .map(|(action, label)| {
+                    let keys = find_keys_for_actions(
+                        keymap,
+                        std::slice::from_ref(action),
+                        true,
+                        key_options,
+                    );
+                    (label.to_string(), keys, HintStyle::default())
+                })
+                .collect();
+            entries.extend(custom_hints(mode, keymap, options));
+
+            let all_keys: Vec<KeyWithModifier> = entries
+                .iter()
+                .flat_map(|(_, keys, _)| keys.clone())
+                .collect();
+
+            if options.profile == Profile::UnlockFirst {
+                // Normal mode is a leader there, its mode switches plain letters shown as keys
+                for (label, keys, style) in entries {
+                    let highlighted = is_highlighted(&label);
+                    let label = hint_name(&label).replace('_', " ");
+                    add_hint(&mut hints, &keys, &label, colors, options);
+                    if let Some(hint) = hints.last_mut().filter(|hint| hint.label == label) {
+                        if highlighted {
+                            highlight.apply(&mut hint.parts);
+                        }
+                        style.apply(&mut hint.parts);
+                    }
+                }
+                add_return_hint(&mut hints, mode, keymap, colors, options, context);
+            } else if !all_keys.is_empty() {
+                let common_modifiers = get_common_modifiers(all_keys.iter().collect());
+
+                if !common_modifiers.is_empty() {
+                    let saturated_bg = palette_match!(colors.ribbon_unselected.background);
+                    let contrasting_fg = palette_match!(colors.ribbon_unselected.base);
+                    let modifier_str = format_modifier_string(&common_modifiers, key_options);
+
+                    prefix.push(Style::new().paint(" "));
+                    let caps = key_options.caps;
+                    prefix.extend(caps.map(|caps| caps.paint(caps.left, saturated_bg)));
+                    prefix.push(
+                        key_options
+                            .attributes
+                            .apply(Style::new().fg(contrasting_fg).on(saturated_bg).bold())
+                            .paint(format!(" {} ", modifier_str.to_lowercase())),
+                    );
+                    prefix.extend(caps.map(|caps| caps.paint(caps.right, saturated_bg)));
+                }
+                adjacent = true;
+
+                // Add labels only for actions with keybindings, unless unbound ones are requested
+                for (label, keys, style) in entries {
+                    let shown = shown_label(&label, &[], options);
+                    if !keys.is_empty() {
+                        let mut parts = style_description(&shown, colors, &[], options);
+                        if is_highlighted(&label) {
+                            highlight.apply(&mut parts);
+                        }
+                        style.apply(&mut parts);
+                        hints.push(Hint::new(&label, &keys, parts));
+                    } else if options.show_missing {
+                        let mut hint = style_missing(&shown, colors);
+                        style.apply(&mut hint);
+                        if hints.is_empty() || options.separator.is_empty() {
+                            hint.insert(0, Style::new().paint(" "));
+                        }
+                        hints.push(Hint::new(&label, &keys, hint));
+                    }
+                }
+            }
+
+            let quick_keys = QUICK_KEY_GROUPS.iter().filter(|_| options.quick_keys);
+            for (groups, label) in quick_keys.chain(NORMAL_MODE_TOGGLES) {
+                let keys = find_keys_for_action_groups(keymap, groups, key_options);
+                let mut parts = style_hint(&keys, label, colors, options);
+                if parts.is_empty() {
+                    continue;
+                }
+                // Set apart from the labels, which follow each other without gaps
+                if adjacent && options.separator.is_empty() {
+                    parts.insert(0, Style::new().paint(" "));
+                }
+                // They make way for the mode switches when the line is too short
+                hints.push(Hint {
+                    priority: Priority::Low,
+                    ..Hint::new(label, &keys, parts)
+                });
+            }
+        }
+        InputMode::Locked if options.locked_indicator.is_some() => {
+            let less_saturated_bg = palette_match!(colors.text_unselected.background);
+            let emphasis_fg = palette_match!(colors.text_unselected.emphasis_2);
+            let indicator = options.locked_indicator.as_deref().unwrap_or_default();
+            let parts = vec![Style::new()
+                .fg(emphasis_fg)
+                .on(less_saturated_bg)
+                .bold()
+                .paint(format!(" {} ", indicator))];
+            hints.push(Hint {
+                name: "locked".to_string(),
+                priority: Priority::High,
+                ..Hint::new(indicator, &[], parts)
+            });
+        }
+        _ => {
+            // The next key goes to the prefix table, which a badge keeps in view
+            let tmux_indicator = options
+                .tmux_indicator
+                .as_deref()
+                .filter(|_| mode == InputMode::Tmux);
+            if let Some(indicator) = tmux_indicator {
+                let badge_bg = palette_match!(colors.ribbon_selected.background);
+                let badge_fg = palette_match!(colors.ribbon_selected.base);
+                let parts = vec![Style::new()
+                    .fg(badge_fg)
+                    .on(badge_bg)
+                    .bold()
+                    .paint(format!(" {} ", indicator))];
+                hints.push(Hint {
+                    name: "prefix".to_string(),
+                    priority: Priority::High,
+                    ..Hint::new(indicator, &[], parts)
+                });
+            }
+
+            if mode == InputMode::Locked && options.locked_show_bindings {
+                for (label, keys) in locked_bindings(keymap, key_options) {
+                    add_hint(&mut hints, &keys, &label, colors, options);
+                }
+            }
+
+            let mut specs = mode_hints(mode);
+            if !options.separate_splits {
+                group_splits(&mut specs, keymap, key_options);
+            }
+            if options.merge_pages {
+                merge_pages(&mut specs);
+            }
+            for spec in specs {
+                let at = hints.len();
+                let keys = match spec.keys {
+                    HintKeys::Return => {
+                        add_return_hint(&mut hints, mode, keymap, colors, options, context);
+                        continue;
+                    }
+                    HintKeys::Select => select_keys.clone(),
+                    ref keys => keys.find(keymap, key_options),
+                };
+                let label = match spec.label {
+                    "select" => select_label,
+                    // Without arrow fonts the split arrows read better spelled out
+                    "→" if context.simplified_ui => "split right",
+                    "↓" if context.simplified_ui => "split down",
+                    "split →↓" if context.simplified_ui => "split",
+                    "page/½page" if context.simplified_ui => "page/half",
+                    "break ←" if context.simplified_ui => "break left",
+                    "break →" if context.simplified_ui => "break right",
+                    label => label,
+                };
+                let is_noop = spec.category.is_contextual()
+                    && spec
+                        .keys
+                        .first_action()
+                        .is_some_and(|action| context.is_noop(action));
+                add_contextual_hint(&mut hints, &keys, label, colors, options, is_noop);
+                for hint in &mut hints[at..] {
+                    hint.priority = spec.priority;
+                }
+            }
+        }
+    }
+
+    // Normal mode lists its custom hints with the rest; elsewhere they go before the hint for
+    // leaving the mode, which stays last
+    if mode != InputMode::Normal
+        && !(mode == InputMode::Locked && options.locked_indicator.is_some())
+    {
+        let mut custom = vec![];
+        for (label, keys, style) in custom_hints(mode, keymap, options) {
+            add_hint(&mut custom, &keys, &label, colors, options);
+            if let Some(hint) = custom.last_mut().filter(|hint| hint.label == label) {
+                style.apply(&mut hint.parts);
+            }
+        }
+        let at = match hints.last() {
+            Some(hint) if hint.label == select_label => hints.len() - 1,
+            _ => hints.len(),
+        };
+        hints.splice(at..at, custom);
+    }
+
+    if let Some(relabels) = options.relabels.get(&mode) {
+        for hint in hints.iter_mut().filter(|hint| !hint.keys.is_empty()) {
+            let relabel = relabels
+                .iter()
+                .find(|(name, _)| hint_name_matches(name, &hint.name));
+            if let Some((_, label)) = relabel {
+                let parts = style_entry(mode, &hint.keys, label, colors, options);
+                *hint = Hint {
+                    name: hint.name.clone(),
+                    priority: hint.priority,
+                    ..Hint::new(label, &hint.keys, parts)
+                };
+            }
+        }
+    }
+
+    if let Some(layout) = options.layouts.get(&mode) {
+        hints = apply_layout(hints, layout, mode, keymap, colors, options);
+    }
+
+    if options.ribbons {
+        // Normal mode's keys go without the modifier its prefix already shows
+        let shown_modifiers = if adjacent {
+            get_common_modifiers(hints.iter().flat_map(|hint| hint.keys.iter()).collect())
+        } else {
+            vec![]
+        };
+        let arrows = !context.simplified_ui;
+        for hint in &mut hints {
+            if hint.name == "mode_badge" {
+                hint.parts = style_ribbon("", &hint.label.to_uppercase(), colors, true, arrows);
+            } else if !hint.keys.is_empty() {
+                let keys = key_label(&hint.keys, &shown_modifiers, key_options);
+                let label = shown_label(&hint.label, &hint.keys, options);
+                let selected = is_highlighted(&hint.label);
+                hint.parts = style_ribbon(&keys, &label, colors, selected, arrows);
+            }
+        }
+        adjacent = true;
+    }
+
+    if context.simplified_ui {
+        let parts = prefix
+            .iter_mut()
+            .chain(hints.iter_mut().flat_map(|hint| hint.parts.iter_mut()));
+        for part in parts {
+            *part.text_mut() = ascii_glyphs(part.text_mut());
+        }
+    }
+
+    hints.retain(|hint| !options.is_hidden(mode, &hint.name));
+    // A layout already picks and orders the hints
+    let order = options.hint_order.get(&mode);
+    if let (None, Some(order)) = (options.layouts.get(&mode), order) {
+        hints = order_hints(hints, order);
+    }
+    let max_hints = options.max_hints_for(mode);
+    let cut = hints
+        .iter()
+        .enumerate()
+        .filter(|(_, hint)| !hint.decoration)
+        .nth(max_hints)
+        .map(|(idx, _)| idx);
+    if let (true, Some(cut)) = (max_hints > 0, cut) {
+        // Text leading up to the first hint left out goes with it
+        let end = hints[..cut]
+            .iter()
+            .rposition(|hint| !hint.decoration)
+            .map_or(0, |idx| idx + 1);
+        hints.truncate(end);
+    }
+    if options.reverse {
+        hints.reverse();
+    }
+    if let Some((text, style)) = options.prefixes.get(&mode) {
+        prefix.insert(0, style.paint(text));
+    }
+    if options.floating_indicator && context.floating_visible && context.floating_pane_count > 0 {
+        let keys =
+            find_keys_for_actions(keymap, &[Action::ToggleFloatingPanes], false, key_options);
+        let label = format!("{} floating", context.floating_pane_count);
+        let mut parts = vec![Style::new().paint(" ")];
+        parts.extend(style_key_with_modifier(&keys, colors, key_options));
+        parts.extend(style_description(&label, colors, &[], options));
+        prefix.splice(0..0, parts);
+    }
+    // Ahead of everything else and never cut, as typing into every pane by mistake is costly
+    if let (true, Some(indicator)) = (context.sync_active, &options.sync_indicator) {
+        prefix.splice(0..0, sync_badge(indicator, colors));
+    }
+    let suffix = options
+        .suffixes
+        .get(&mode)
+        .map(|(text, style)| style.paint(text))
+        .into_iter()
+        .collect();
+    ModeHints {
+        prefix,
+        adjacent,
+        hints,
+        suffix,
+    }
+}
+
+/// Appends e.g. "#2: RESIZE" for every other connected client that is in a modal mode.
+pub fn add_other_client_modes(
+    parts: &mut Vec<StyledString>,
+    other_client_modes: &BTreeMap<ClientId, InputMode>,
+    palette: &Styling,
+) {
+    if other_client_modes.is_empty() {
+        return;
+    }
+    let less_saturated_bg = palette_match!(palette.text_unselected.background);
+    let emphasis_fg = palette_match!(palette.text_unselected.emphasis_2);
+
+    let indicator = other_client_modes
+        .iter()
+        .map(|(client_id, mode)| format!("#{}: {}", client_id, mode_name(*mode).to_uppercase()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    parts.push(Style::new().paint(" "));
+    parts.push(
+        Style::new()
+            .fg(emphasis_fg)
+            .on(less_saturated_bg)
+            .bold()
+            .paint(format!(" {} ", indicator)),
+    );
+}
@@ -0,0 +1,196 @@
+//! Scanning a mode's keymap for the keys bound to actions.
+
+use crate::actions::ActionPattern;
+use crate::options::{ActionMatch, KeyOptions};
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+
+pub const TO_NORMAL: Action = Action::SwitchToMode(InputMode::Normal);
+
+pub fn get_common_modifiers(mut key_bindings: Vec<&KeyWithModifier>) -> Vec<KeyModifier> {
+    if key_bindings.is_empty() {
+        return vec![];
+    }
+    let mut common_modifiers = key_bindings.pop().unwrap().key_modifiers.clone();
+    for key in key_bindings {
+        common_modifiers = common_modifiers
+            .intersection(&key.key_modifiers)
+            .cloned()
+            .collect();
+    }
+    common_modifiers.into_iter().collect()
+}
+
+pub fn find_keys_for_actions(
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    target_actions: &[Action],
+    exact_match: bool,
+    key_options: &KeyOptions,
+) -> Vec<KeyWithModifier> {
+    let keys = keymap
+        .iter()
+        .filter_map(|(key, key_actions)| {
+            if exact_match {
+                let matching = key_actions
+                    .iter()
+                    .zip(target_actions)
+                    .filter(|(a, b)| a.shallow_eq(b))
+                    .count();
+                if matching == key_actions.len() && matching == target_actions.len() {
+                    Some(key.clone())
+                } else {
+                    None
+                }
+            } else {
+                match (key_actions.iter().next(), target_actions.iter().next()) {
+                    (Some(a), Some(b)) if a.shallow_eq(b) => Some(key.clone()),
+                    _ => None,
+                }
+            }
+        })
+        .collect();
+    key_options.apply(keys)
+}
+
+/// The keys whose actions match `patterns` as `matching` asks.
+pub fn find_keys_matching(
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    patterns: &[ActionPattern],
+    matching: ActionMatch,
+    key_options: &KeyOptions,
+) -> Vec<KeyWithModifier> {
+    let keys = keymap
+        .iter()
+        .filter(|(_, key_actions)| matching.matches(key_actions, patterns))
+        .map(|(key, _)| key.clone())
+        .collect();
+    key_options.apply(keys)
+}
+
+pub fn find_keys_for_action_groups(
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    action_groups: &[&[Action]],
+    key_options: &KeyOptions,
+) -> Vec<KeyWithModifier> {
+    let mut keys: Vec<KeyWithModifier> = action_groups
+        .iter()
+        .flat_map(|actions| find_keys_for_actions(keymap, actions, true, key_options))
+        .collect();
+    key_options.sort_group(&mut keys);
+    keys
+}
+
+/// The keys that launch the plugin called `plugin_name`, ordered and limited like the keys of
+/// any other action.
+pub fn plugin_keys(
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    plugin_name: &str,
+    key_options: &KeyOptions,
+) -> Vec<KeyWithModifier> {
+    let keys = keymap
+        .iter()
+        .filter(|(_, key_actions)| {
+            key_actions
+                .iter()
+                .any(|action| action.launches_plugin(plugin_name))
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+    key_options.apply(keys)
+}
+
+/// The first of `targets` that some key in the keymap switches to, with those keys.
+pub fn find_keys_to_mode(
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    targets: &[InputMode],
+    key_options: &KeyOptions,
+) -> Option<(InputMode, Vec<KeyWithModifier>)> {
+    targets.iter().find_map(|&target| {
+        let keys =
+            find_keys_for_actions(keymap, &[Action::SwitchToMode(target)], true, key_options);
+        (!keys.is_empty()).then_some((target, keys))
+    })
+}
+
+/// The key shown for leaving a mode: `preferred` (Enter unless configured) if it returns to the
+/// base mode, otherwise the first key that does. Keys back to Normal mode stand in when none
+/// return to the base mode.
+pub fn get_select_key(
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    base_mode: InputMode,
+    preferred: Option<&KeyWithModifier>,
+) -> Vec<KeyWithModifier> {
+    let to_base_keys = find_keys_to_mode(
+        keymap,
+        &[base_mode, InputMode::Normal],
+        &KeyOptions::default(),
+    )
+    .map(|(_, keys)| keys)
+    .unwrap_or_default();
+    let enter = KeyWithModifier::new(BareKey::Enter);
+    let preferred = preferred.unwrap_or(&enter);
+    if to_base_keys.contains(preferred) {
+        vec![preferred.clone()]
+    } else {
+        to_base_keys.into_iter().take(1).collect()
+    }
+}
+
+pub fn get_keymap_for_mode(mode_info: &ModeInfo) -> Vec<(KeyWithModifier, Vec<Action>)> {
+    match mode_info.mode {
+        InputMode::Normal => mode_info.get_keybinds_for_mode(InputMode::Normal),
+        InputMode::Pane => mode_info.get_keybinds_for_mode(InputMode::Pane),
+        InputMode::Tab => mode_info.get_keybinds_for_mode(InputMode::Tab),
+        InputMode::Resize => mode_info.get_keybinds_for_mode(InputMode::Resize),
+        InputMode::Move => mode_info.get_keybinds_for_mode(InputMode::Move),
+        InputMode::Scroll => mode_info.get_keybinds_for_mode(InputMode::Scroll),
+        InputMode::Search => mode_info.get_keybinds_for_mode(InputMode::Search),
+        InputMode::Session => mode_info.get_keybinds_for_mode(InputMode::Session),
+        _ => mode_info.get_mode_keybinds(),
+    }
+}
+
+/// Parses zellij's key syntax, e.g. "Ctrl p", "Alt Shift Left", "PageDown", "F1".
+pub fn parse_key(spec: &str) -> Result<KeyWithModifier, String> {
+    let mut words: Vec<&str> = spec.split_whitespace().collect();
+    let bare = words.pop().ok_or("empty key")?;
+    let bare_key = match bare.to_lowercase().as_str() {
+        "pagedown" => BareKey::PageDown,
+        "pageup" => BareKey::PageUp,
+        "left" => BareKey::Left,
+        "down" => BareKey::Down,
+        "up" => BareKey::Up,
+        "right" => BareKey::Right,
+        "home" => BareKey::Home,
+        "end" => BareKey::End,
+        "backspace" => BareKey::Backspace,
+        "delete" => BareKey::Delete,
+        "insert" => BareKey::Insert,
+        "space" => BareKey::Char(' '),
+        "tab" => BareKey::Tab,
+        "esc" => BareKey::Esc,
+        "enter" => BareKey::Enter,
+        lower if lower.len() > 1 && lower.starts_with('f') => lower[1..]
+            .parse()
+            .map(BareKey::F)
+            .map_err(|_| format!("unknown key {:?}", spec))?,
+        _ => {
+            let mut chars = bare.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => BareKey::Char(ch),
+                _ => return Err(format!("unknown key {:?}", spec)),
+            }
+        }
+    };
+    let mut key = KeyWithModifier::new(bare_key);
+    for modifier in words {
+        key = match modifier.to_lowercase().as_str() {
+            "ctrl" => key.with_ctrl_modifier(),
+            "alt" => key.with_alt_modifier(),
+            "shift" => key.with_shift_modifier(),
+            "super" => key.with_super_modifier(),
+            _ => return Err(format!("unknown modifier in {:?}", spec)),
+        };
+    }
+    Ok(key)
+}
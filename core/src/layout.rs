@@ -0,0 +1,142 @@
+//! Hint lines declared node by node in KDL, e.g.
+//!
+//! ```kdl
+//! mode-badge
+//! hint "new"
+//! key-hint label="frames" match="prefix" { TogglePaneFrames; }
+//! hint "quit" fg="red" bold=true
+//! text " | " fg="8"
+//! hint "select"
+//! ```
+
+use crate::actions::{parse_action_pattern, parse_action_patterns, ActionPattern};
+use crate::options::{ActionMatch, CustomHint, HintStyle};
+use crate::style::Colour;
+use kdl::{KdlDocument, KdlNode, KdlValue};
+
+/// One segment of a declared hint line.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutItem {
+    /// The hints a name matches, as for `hint_order`, optionally relabelled and restyled.
+    Hint {
+        name: String,
+        label: Option<String>,
+        style: HintStyle,
+    },
+    /// A hint for the keys bound to the given actions.
+    KeyHint(CustomHint),
+    /// Literal text, drawn without gaps around it; spacers are blank text.
+    Text { text: String, style: HintStyle },
+    /// The name of the current mode.
+    ModeBadge,
+}
+
+/// Parses a hint line declared as `hint`, `key-hint`, `text`, `spacer` and `mode-badge` nodes.
+pub fn parse_layout(src: &str) -> Result<Vec<LayoutItem>, String> {
+    let document: KdlDocument = src
+        .parse()
+        .map_err(|error: kdl::KdlError| error.to_string())?;
+    document.nodes().iter().map(layout_item).collect()
+}
+
+/// An argument or property value as text, e.g. "2" for `spacer 2`.
+fn text(value: &KdlValue) -> String {
+    match value.as_string() {
+        Some(text) => text.to_string(),
+        None => value.to_string(),
+    }
+}
+
+fn first_arg(node: &KdlNode) -> Option<String> {
+    node.entries()
+        .iter()
+        .find(|entry| entry.name().is_none())
+        .map(|entry| text(entry.value()))
+}
+
+fn prop(node: &KdlNode, name: &str) -> Option<String> {
+    node.entries()
+        .iter()
+        .find(|entry| entry.name().is_some_and(|key| key.value() == name))
+        .map(|entry| text(entry.value()))
+}
+
+fn layout_item(node: &KdlNode) -> Result<LayoutItem, String> {
+    let name = node.name().value();
+    let arg = |what: &str| first_arg(node).ok_or_else(|| format!("{} needs {}", name, what));
+    Ok(match name {
+        "hint" => LayoutItem::Hint {
+            name: arg("a hint name")?,
+            label: prop(node, "label"),
+            style: hint_style(node)?,
+        },
+        "key-hint" => {
+            let label = prop(node, "label").ok_or("key-hint needs a label")?;
+            let matching = match prop(node, "match") {
+                Some(value) => ActionMatch::parse(&value)
+                    .ok_or_else(|| format!("invalid match: {:?}", value))?,
+                None => ActionMatch::default(),
+            };
+            LayoutItem::KeyHint(CustomHint {
+                label,
+                actions: key_hint_actions(node)?,
+                matching,
+                style: hint_style(node)?,
+            })
+        }
+        "text" => LayoutItem::Text {
+            text: arg("its text")?,
+            style: hint_style(node)?,
+        },
+        "spacer" => {
+            let width = match first_arg(node) {
+                Some(width) => width
+                    .parse()
+                    .map_err(|_| format!("invalid spacer width: {:?}", width))?,
+                None => 1,
+            };
+            LayoutItem::Text {
+                text: " ".repeat(width),
+                style: hint_style(node)?,
+            }
+        }
+        "mode-badge" => LayoutItem::ModeBadge,
+        other => return Err(format!("unknown layout node {:?}", other)),
+    })
+}
+
+/// The actions of a `key-hint`, given as an `action` property or as child nodes.
+fn key_hint_actions(node: &KdlNode) -> Result<Vec<ActionPattern>, String> {
+    let actions = match prop(node, "action") {
+        Some(action) => parse_action_patterns(&action)?,
+        None => node
+            .children()
+            .map(KdlDocument::nodes)
+            .unwrap_or_default()
+            .iter()
+            .map(parse_action_pattern)
+            .collect::<Result<_, _>>()?,
+    };
+    if actions.is_empty() {
+        return Err("key-hint needs an action".to_string());
+    }
+    Ok(actions)
+}
+
+/// The `fg`, `bg` and `bold` properties of a hint or text.
+fn hint_style(node: &KdlNode) -> Result<HintStyle, String> {
+    let colour = |name: &str| {
+        prop(node, name)
+            .map(|value| {
+                Colour::parse(&value).ok_or_else(|| format!("invalid {}: {:?}", name, value))
+            })
+            .transpose()
+    };
+    Ok(HintStyle {
+        fg: colour("fg")?,
+        bg: colour("bg")?,
+        bold: prop(node, "bold")
+            .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
+            .unwrap_or(false),
+    })
+}
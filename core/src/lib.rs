@@ -0,0 +1,224 @@
+//! The hint engine behind the zjstatus-hints plugin: scans a mode's keymap for the keys bound
+//! to common actions and renders them as a styled, single line of hints.
+//!
+//! The plugin is a thin wrapper that feeds this with zellij's events and pipes the result to
+//! zjstatus; other plugins can use it to render the same hints themselves. `one_line_ui` does
+//! it all from a `ModeInfo` for a fork of zellij's own status bar, and `collect_hints_for_mode`,
+//! `fit_hints` and `format_line` are its steps for anyone who needs them apart.
+
+pub mod actions;
+pub mod ansi;
+pub mod components;
+pub mod config;
+pub mod context;
+pub mod hints;
+pub mod keys;
+pub mod layout;
+pub mod mode;
+pub mod options;
+pub mod preset;
+pub mod schema;
+pub mod style;
+pub mod table;
+pub mod theme;
+pub mod verbose;
+
+pub use context::HintContext;
+pub use hints::{
+    add_other_client_modes, base_mode_parts, collect_hints_for_mode, join_hints, keymap_hints,
+    paginate_hints, render_hints_for_mode, ModeHints,
+};
+pub use keys::{
+    find_keys_for_action_groups, find_keys_for_actions, find_keys_matching, get_keymap_for_mode,
+    parse_key,
+};
+pub use layout::{parse_layout, LayoutItem};
+pub use mode::{mode_name, parse_mode, takes_text, ALL_MODES};
+pub use options::{
+    ActionMatch, Align, BaseModeDisplay, ContextHints, CustomHint, HighlightMode, HintStyle,
+    IconTheme, KeyCaps, KeyNames, KeyOptions, KeyOrder, KeysPerAction, Labels, LineOptions,
+    ModifierOrder, Modifiers, OverflowPosition, Profile, RenderMode, RenderOptions, SuperLabel,
+    TextAttributes,
+};
+pub use preset::Preset;
+pub use style::{StyledString, StyledStrings};
+pub use theme::Theme;
+pub use verbose::describe_hints_for_mode;
+
+use ansi::{calculate_visible_length, truncate_ansi_string, truncate_ansi_string_start};
+use zellij_tile::prelude::{ModeInfo, Styling};
+
+/// A rendered stretch of a status bar with its width in columns, as zellij's status-bar plugin
+/// lays out its line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinePart {
+    /// The text, with its ANSI escapes.
+    pub part: String,
+    pub len: usize,
+}
+
+impl LinePart {
+    pub fn new(part: String) -> Self {
+        let len = calculate_visible_length(&part);
+        LinePart { part, len }
+    }
+}
+
+/// The hints for the mode `mode_info` is in, drawn with its theme and cut to `max_len` columns
+/// (0 for no limit) as the plugin would pipe them, for status bars to draw in place of their own
+/// `one_line_ui`.
+pub fn one_line_ui(
+    mode_info: &ModeInfo,
+    max_len: usize,
+    options: &RenderOptions,
+    context: &HintContext,
+) -> LinePart {
+    let mode = mode_info.mode;
+    let colors = &mode_info.style.colors;
+    let keymap = get_keymap_for_mode(mode_info);
+    let line = LineOptions {
+        max_length: max_len,
+        ..LineOptions::default()
+    }
+    .for_mode(mode);
+    let mode_hints = collect_hints_for_mode(mode, &keymap, colors, options, context);
+    let parts = fit_hints(mode_hints, colors, options, &line);
+    LinePart::new(format_line(&parts, &line))
+}
+
+/// Page `page` of the hints, counted from 0 and wrapping around either way, when they're split
+/// into pages fitting `line.max_length`. A marker such as " 2/3" in the overflow style follows
+/// them when there's more than one page.
+pub fn render_hint_page(
+    mode_hints: ModeHints,
+    page: isize,
+    colors: &Styling,
+    options: &RenderOptions,
+    line: &LineOptions,
+) -> Vec<StyledString> {
+    if line.max_length == 0 {
+        return join_hints(mode_hints, colors, options);
+    }
+    // Room for the widest marker there could be
+    let most_pages = mode_hints.hints.len().max(1).to_string().len();
+    let marker_width = 2 + 2 * most_pages;
+    let width = line
+        .max_length
+        .saturating_sub(line.padding_left + line.padding_right + marker_width);
+    let mut pages = paginate_hints(mode_hints, width, colors, options);
+    let count = pages.len();
+    let page = page.rem_euclid(count as isize) as usize;
+    let mut parts = join_hints(pages.swap_remove(page), colors, options);
+    if count > 1 {
+        parts.push(
+            line.overflow_style
+                .paint(format!(" {}/{}", page + 1, count)),
+        );
+    }
+    parts
+}
+
+/// The hints joined up, leaving out whole hints rather than cutting one in half when they don't
+/// fit `line.max_length`: those of the lowest `Priority` first, and of equal ones those from the
+/// end (or the start, with `keep_end`). The overflow string stands in for those left out. If not
+/// even one hint fits, they're all kept for `format_line` to cut.
+pub fn fit_hints(
+    mode_hints: ModeHints,
+    colors: &Styling,
+    options: &RenderOptions,
+    line: &LineOptions,
+) -> Vec<StyledString> {
+    let all = join_hints(mode_hints.clone(), colors, options);
+    let available = line
+        .max_length
+        .saturating_sub(line.padding_left + line.padding_right);
+    if line.max_length == 0 || visible_width(&all) <= available {
+        return all;
+    }
+    let overflow = line.overflow_style.paint(line.overflow_str.clone());
+    let room = available.saturating_sub(visible_width(std::slice::from_ref(&overflow)));
+    let count = mode_hints.hints.len();
+    let mut dropping: Vec<usize> = (0..count).collect();
+    dropping.sort_by_key(|&at| {
+        let from_end = if line.keep_end { at } else { count - at };
+        (mode_hints.hints[at].priority, from_end)
+    });
+    for dropped in 1..count {
+        let mut fitting = mode_hints.clone();
+        let mut at = 0;
+        fitting.hints.retain(|_| {
+            at += 1;
+            !dropping[..dropped].contains(&(at - 1))
+        });
+        // The overflow string stands in for the hints left out, ahead of the suffix
+        let suffix_len = fitting.suffix.len();
+        let mut parts = join_hints(fitting, colors, options);
+        if visible_width(&parts) <= room {
+            match line.overflow_position {
+                OverflowPosition::Before => parts.insert(0, overflow),
+                OverflowPosition::After => parts.insert(parts.len() - suffix_len, overflow),
+            }
+            return parts;
+        }
+    }
+    all
+}
+
+fn visible_width(parts: &[StyledString]) -> usize {
+    calculate_visible_length(&StyledStrings(parts).to_string())
+}
+
+/// Joins rendered hint parts into the final output line, padded and truncated per `options`.
+/// The padding always survives truncation; only the hints are cut. Lines shorter than
+/// `min_length`, or than `max_length` unless left-aligned, are then filled up to it.
+pub fn format_line(parts: &[StyledString], options: &LineOptions) -> String {
+    let mut hints = StyledStrings(parts).to_string();
+
+    let padding = options.padding_left + options.padding_right;
+    if options.max_length > 0 && calculate_visible_length(&hints) + padding > options.max_length {
+        let available = options.max_length.saturating_sub(padding);
+        let overflow = options
+            .overflow_style
+            .paint(options.overflow_str.clone())
+            .to_string();
+        let kept = available.saturating_sub(calculate_visible_length(&overflow));
+        hints = match (options.keep_end, options.overflow_position) {
+            (false, OverflowPosition::After) => truncate_ansi_string(&hints, &overflow, available),
+            (false, OverflowPosition::Before) => {
+                format!("{}{}", overflow, truncate_ansi_string(&hints, "", kept))
+            }
+            (true, OverflowPosition::Before) => {
+                truncate_ansi_string_start(&hints, &overflow, available)
+            }
+            (true, OverflowPosition::After) => {
+                format!(
+                    "{}{}",
+                    truncate_ansi_string_start(&hints, "", kept),
+                    overflow
+                )
+            }
+        };
+    }
+    let line = format!(
+        "{}{}{}",
+        " ".repeat(options.padding_left),
+        hints,
+        " ".repeat(options.padding_right)
+    );
+
+    // Centered or right-aligned hints sit within the whole `max_length` they may take up
+    let width = match options.align {
+        Align::Left => options.min_length,
+        Align::Center | Align::Right => options.min_length.max(options.max_length),
+    };
+    let fill = width.saturating_sub(calculate_visible_length(&line));
+    let (before, after) = match options.align {
+        Align::Left => (0, fill),
+        Align::Center => (fill / 2, fill - fill / 2),
+        Align::Right => (fill, 0),
+    };
+    format!("{}{}{}", " ".repeat(before), line, " ".repeat(after))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,67 @@
+//! Names for input modes as used in configuration and between plugin instances.
+
+use zellij_tile::prelude::*;
+
+pub const ALL_MODES: [InputMode; 14] = [
+    InputMode::Normal,
+    InputMode::Locked,
+    InputMode::Resize,
+    InputMode::Pane,
+    InputMode::Tab,
+    InputMode::Scroll,
+    InputMode::EnterSearch,
+    InputMode::Search,
+    InputMode::RenameTab,
+    InputMode::RenamePane,
+    InputMode::Session,
+    InputMode::Move,
+    InputMode::Prompt,
+    InputMode::Tmux,
+];
+
+pub fn mode_name(mode: InputMode) -> &'static str {
+    match mode {
+        InputMode::Normal => "normal",
+        InputMode::Locked => "locked",
+        InputMode::Resize => "resize",
+        InputMode::Pane => "pane",
+        InputMode::Tab => "tab",
+        InputMode::Scroll => "scroll",
+        InputMode::EnterSearch => "entersearch",
+        InputMode::Search => "search",
+        InputMode::RenameTab => "renametab",
+        InputMode::RenamePane => "renamepane",
+        InputMode::Session => "session",
+        InputMode::Move => "move",
+        InputMode::Prompt => "prompt",
+        InputMode::Tmux => "tmux",
+    }
+}
+
+/// Modes that take text, where most keys type rather than act.
+pub fn takes_text(mode: InputMode) -> bool {
+    matches!(
+        mode,
+        InputMode::RenameTab | InputMode::RenamePane | InputMode::EnterSearch
+    )
+}
+
+pub fn parse_mode(name: &str) -> Option<InputMode> {
+    match name.trim().to_lowercase().as_str() {
+        "normal" => Some(InputMode::Normal),
+        "locked" => Some(InputMode::Locked),
+        "resize" => Some(InputMode::Resize),
+        "pane" => Some(InputMode::Pane),
+        "tab" => Some(InputMode::Tab),
+        "scroll" => Some(InputMode::Scroll),
+        "entersearch" => Some(InputMode::EnterSearch),
+        "search" => Some(InputMode::Search),
+        "renametab" => Some(InputMode::RenameTab),
+        "renamepane" => Some(InputMode::RenamePane),
+        "session" => Some(InputMode::Session),
+        "move" => Some(InputMode::Move),
+        "prompt" => Some(InputMode::Prompt),
+        "tmux" => Some(InputMode::Tmux),
+        _ => None,
+    }
+}
@@ -0,0 +1,832 @@
+//! Options controlling which keys and hints are rendered.
+
+use crate::actions::ActionPattern;
+use crate::keys::parse_key;
+use crate::layout::LayoutItem;
+use crate::mode::parse_mode;
+use crate::style::{Colour, Style, StyledString};
+use std::collections::HashMap;
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+
+/// How many of the keys bound to a single action are shown in a hint.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum KeysPerAction {
+    #[default]
+    All,
+    Limit(usize),
+}
+
+impl KeysPerAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "all" => Some(KeysPerAction::All),
+            "first" => Some(KeysPerAction::Limit(1)),
+            n => n.parse().ok().map(KeysPerAction::Limit),
+        }
+    }
+
+    pub fn apply(self, mut keys: Vec<KeyWithModifier>) -> Vec<KeyWithModifier> {
+        if let KeysPerAction::Limit(n) = self {
+            keys.truncate(n.max(1));
+        }
+        keys
+    }
+}
+
+/// Which kind of key is listed first when an action group has several.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum KeyOrder {
+    #[default]
+    Letters,
+    Arrows,
+    Keymap,
+}
+
+impl KeyOrder {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "letters" => Some(KeyOrder::Letters),
+            "arrows" => Some(KeyOrder::Arrows),
+            "keymap" => Some(KeyOrder::Keymap),
+            _ => None,
+        }
+    }
+
+    pub fn rank(self, key: &KeyWithModifier) -> usize {
+        let is_arrow = matches!(
+            key.bare_key,
+            BareKey::Left | BareKey::Down | BareKey::Up | BareKey::Right
+        );
+        let is_char = matches!(key.bare_key, BareKey::Char(_));
+        match (self, is_char, is_arrow) {
+            (KeyOrder::Letters, true, _) | (KeyOrder::Arrows, _, true) => 0,
+            (KeyOrder::Letters, _, true) | (KeyOrder::Arrows, true, _) => 1,
+            _ => 2,
+        }
+    }
+}
+
+/// A set of key modifiers to filter keys by, where "none" stands for keys without any.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub super_: bool,
+    pub none: bool,
+}
+
+impl Modifiers {
+    /// Modifier names separated by commas or spaces, e.g. "Alt, Ctrl" or "alt none".
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut modifiers = Modifiers::default();
+        for name in value.split(|c: char| c == ',' || c.is_whitespace()) {
+            let flag = match name.to_lowercase().as_str() {
+                "" => continue,
+                "ctrl" | "control" => &mut modifiers.ctrl,
+                "alt" => &mut modifiers.alt,
+                "shift" => &mut modifiers.shift,
+                "super" => &mut modifiers.super_,
+                "none" => &mut modifiers.none,
+                _ => return None,
+            };
+            *flag = true;
+        }
+        Some(modifiers)
+    }
+
+    fn contains(self, modifier: &KeyModifier) -> bool {
+        match modifier {
+            KeyModifier::Ctrl => self.ctrl,
+            KeyModifier::Alt => self.alt,
+            KeyModifier::Shift => self.shift,
+            KeyModifier::Super => self.super_,
+        }
+    }
+
+    /// Whether `key` is pressed with modifiers of this set only.
+    pub fn allows(self, key: &KeyWithModifier) -> bool {
+        if key.key_modifiers.is_empty() {
+            return self.none;
+        }
+        key.key_modifiers
+            .iter()
+            .all(|modifier| self.contains(modifier))
+    }
+
+    /// Whether `key` is pressed with any modifier of this set.
+    pub fn touches(self, key: &KeyWithModifier) -> bool {
+        if key.key_modifiers.is_empty() {
+            return self.none;
+        }
+        key.key_modifiers
+            .iter()
+            .any(|modifier| self.contains(modifier))
+    }
+}
+
+/// The order modifiers are listed in, e.g. "ctrl-alt" rather than "alt-ctrl".
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ModifierOrder(pub [KeyModifier; 4]);
+
+impl Default for ModifierOrder {
+    fn default() -> Self {
+        ModifierOrder([
+            KeyModifier::Ctrl,
+            KeyModifier::Alt,
+            KeyModifier::Shift,
+            KeyModifier::Super,
+        ])
+    }
+}
+
+impl ModifierOrder {
+    /// Modifier names separated by commas or spaces, e.g. "alt, ctrl". Modifiers left out follow
+    /// in the default order: Ctrl, Alt, Shift, Super.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut order = vec![];
+        for name in value.split(|c: char| c == ',' || c.is_whitespace()) {
+            let modifier = match name.to_lowercase().as_str() {
+                "" => continue,
+                "ctrl" | "control" => KeyModifier::Ctrl,
+                "alt" => KeyModifier::Alt,
+                "shift" => KeyModifier::Shift,
+                "super" => KeyModifier::Super,
+                _ => return None,
+            };
+            if !order.contains(&modifier) {
+                order.push(modifier);
+            }
+        }
+        for modifier in ModifierOrder::default().0 {
+            if !order.contains(&modifier) {
+                order.push(modifier);
+            }
+        }
+        order.try_into().ok().map(ModifierOrder)
+    }
+
+    pub fn sort(self, modifiers: &mut [KeyModifier]) {
+        modifiers.sort_by_key(|modifier| self.0.iter().position(|m| m == modifier));
+    }
+}
+
+/// How the Super modifier is written, e.g. for Cmd bindings sent by macOS terminals.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SuperLabel {
+    #[default]
+    Super,
+    Glyph,
+    Win,
+    Cmd,
+    Meta,
+}
+
+impl SuperLabel {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "super" => Some(SuperLabel::Super),
+            "glyph" | "❖" => Some(SuperLabel::Glyph),
+            "win" => Some(SuperLabel::Win),
+            "cmd" => Some(SuperLabel::Cmd),
+            "meta" => Some(SuperLabel::Meta),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SuperLabel::Super => "Super",
+            SuperLabel::Glyph => "❖",
+            SuperLabel::Win => "Win",
+            SuperLabel::Cmd => "Cmd",
+            SuperLabel::Meta => "Meta",
+        }
+    }
+}
+
+/// Names shown for bare keys in place of zellij's, e.g. "␣" for Space.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyNames(pub Vec<(BareKey, String)>);
+
+impl KeyNames {
+    /// Names written as comma-separated assignments, e.g. "space=␣, backspace=⌫, pageup=PgUp".
+    /// Keys are written as in zellij's keybindings.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut names = vec![];
+        for assignment in spec.split(',').filter(|s| !s.trim().is_empty()) {
+            let (key, name) = assignment
+                .split_once('=')
+                .ok_or_else(|| format!("expected <key>=<name>, got {:?}", assignment.trim()))?;
+            let key = parse_key(key.trim())?;
+            if !key.key_modifiers.is_empty() {
+                return Err(format!("expected a key without modifiers, got {:?}", key));
+            }
+            names.push((key.bare_key, name.trim().to_string()));
+        }
+        Ok(KeyNames(names))
+    }
+
+    /// The configured name of `key`, otherwise zellij's with Space and Backspace spelled out
+    /// readably.
+    pub fn name(&self, key: &BareKey) -> String {
+        if let Some((_, name)) = self.0.iter().find(|(bare_key, _)| bare_key == key) {
+            return name.clone();
+        }
+        match key {
+            BareKey::Char(' ') => "Space".to_string(),
+            BareKey::Backspace => "BS".to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// Glyphs drawn at either end of each key pill, e.g. the rounded "" and "" of Powerline
+/// fonts, in the pill's background color so it blends into the bar.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyCaps {
+    pub left: char,
+    pub right: char,
+    /// The bar's background around the pills; the terminal's default when unset.
+    pub background: Option<Colour>,
+}
+
+impl KeyCaps {
+    /// Caps called `name`: "rounded", "slanted", or two characters of their own, e.g. "[]".
+    pub fn parse(name: &str) -> Option<Self> {
+        let (left, right) = match name.trim().to_lowercase().as_str() {
+            "rounded" => ('\u{e0b6}', '\u{e0b4}'),
+            "slanted" => ('\u{e0ba}', '\u{e0bc}'),
+            other => {
+                let mut chars = other.chars();
+                match (chars.next(), chars.next(), chars.next()) {
+                    (Some(left), Some(right), None) => (left, right),
+                    _ => return None,
+                }
+            }
+        };
+        Some(KeyCaps {
+            left,
+            right,
+            background: None,
+        })
+    }
+
+    /// `cap` blending a pill in `pill_bg` into the bar.
+    pub fn paint(&self, cap: char, pill_bg: Colour) -> StyledString {
+        let style = Style::new().fg(pill_bg);
+        match self.background {
+            Some(background) => style.on(background),
+            None => style,
+        }
+        .paint(cap.to_string())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyOptions {
+    pub per_action: KeysPerAction,
+    pub order: KeyOrder,
+    pub modifier_order: ModifierOrder,
+    pub super_label: SuperLabel,
+    pub key_names: KeyNames,
+    pub attributes: TextAttributes,
+    pub caps: Option<KeyCaps>,
+    /// Keys pressed with other modifiers are left out, in Normal mode only; see `for_mode`.
+    pub only_modifiers: Option<Modifiers>,
+    /// Keys pressed with any of these modifiers are left out, in Normal mode only.
+    pub exclude_modifiers: Modifiers,
+}
+
+impl KeyOptions {
+    /// The names of the modifiers, in the configured order.
+    pub fn modifier_names(&self, modifiers: &[KeyModifier]) -> Vec<&'static str> {
+        let mut modifiers = modifiers.to_vec();
+        self.modifier_order.sort(&mut modifiers);
+        modifiers
+            .into_iter()
+            .map(|modifier| match modifier {
+                KeyModifier::Ctrl => "Ctrl",
+                KeyModifier::Alt => "Alt",
+                KeyModifier::Shift => "Shift",
+                KeyModifier::Super => self.super_label.as_str(),
+            })
+            .collect()
+    }
+
+    pub fn filters_modifiers(&self) -> bool {
+        self.only_modifiers.is_some() || self.exclude_modifiers != Modifiers::default()
+    }
+
+    /// These options for the keys of `mode`. The modifier filters pick among Normal mode's mode
+    /// switches and shortcuts; the other modes mostly bind plain letters, which they would leave
+    /// without keys.
+    pub fn for_mode(&self, mode: InputMode) -> KeyOptions {
+        let mut options = self.clone();
+        if mode != InputMode::Normal {
+            options.only_modifiers = None;
+            options.exclude_modifiers = Modifiers::default();
+        }
+        options
+    }
+
+    /// Filters, orders and limits the keys bound to a single action.
+    pub fn apply(&self, mut keys: Vec<KeyWithModifier>) -> Vec<KeyWithModifier> {
+        keys.retain(|key| {
+            self.only_modifiers.is_none_or(|only| only.allows(key))
+                && !self.exclude_modifiers.touches(key)
+        });
+        if self.order != KeyOrder::Keymap {
+            keys.sort_by_cached_key(|key| {
+                (
+                    self.order.rank(key),
+                    key.key_modifiers.len(),
+                    key.to_string(),
+                )
+            });
+        }
+        self.per_action.apply(keys)
+    }
+
+    /// Orders the keys of an action group by kind only, keeping the group's direction order.
+    pub fn sort_group(&self, keys: &mut [KeyWithModifier]) {
+        if self.order != KeyOrder::Keymap {
+            keys.sort_by_key(|key| self.order.rank(key));
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RenderOptions {
+    pub keys: KeyOptions,
+    /// Render built-in hints whose actions have no key bound as dimmed "✗ label".
+    pub show_missing: bool,
+    /// Follow Normal mode's mode switches with the shortcuts bound in it, such as Alt n.
+    pub quick_keys: bool,
+    /// Keep Pane mode's "→" and "↓" split hints apart instead of grouping them as "split →↓".
+    pub separate_splits: bool,
+    /// Show Scroll and Search mode's "page" and "half page" hints as one, "page/½page".
+    pub merge_pages: bool,
+    pub context_hints: ContextHints,
+    pub profile: Profile,
+    /// Draw each hint as a ribbon of zellij's status bar.
+    pub ribbons: bool,
+    /// List the keys bound in Locked mode besides the ones unlocking it.
+    pub locked_show_bindings: bool,
+    pub highlight_mode: HighlightMode,
+    pub labels: Labels,
+    pub icon_theme: IconTheme,
+    /// Leave labels without their key's letter unhighlighted instead of highlighting their first
+    /// letter.
+    pub strict_mnemonics: bool,
+    pub label_attributes: TextAttributes,
+    /// Drawn between hints instead of the usual single space; empty for the space.
+    pub separator: String,
+    /// Most hints rendered in any mode; 0 for no limit.
+    pub max_hints: usize,
+    /// Per-mode overrides of `max_hints`.
+    pub mode_max_hints: HashMap<InputMode, usize>,
+    /// List the hints last to first, so the first ones end up nearest the right edge.
+    pub reverse: bool,
+    /// Names of the built-in hints left out of each mode. A name also hides the hints it
+    /// prefixes, so `split` covers `split_right` and `split_down`.
+    pub hidden_hints: HashMap<InputMode, Vec<String>>,
+    /// Names of the hints to show in each mode, in order; modes without an entry show all their
+    /// built-in hints. In Normal mode, the name of another mode adds an entry switching to it.
+    pub hint_order: HashMap<InputMode, Vec<String>>,
+    /// Hints for actions of the user's choosing, after the built-in ones of each mode.
+    pub custom_hints: HashMap<InputMode, Vec<CustomHint>>,
+    /// Labels replacing those of the hints a name matches, e.g. "split" for `split_right`.
+    pub relabels: HashMap<InputMode, Vec<(String, String)>>,
+    /// Text drawn before each mode's hints, kept whole when they're cut.
+    pub prefixes: HashMap<InputMode, (String, HintStyle)>,
+    /// Text drawn after each mode's hints, kept whole when they're cut.
+    pub suffixes: HashMap<InputMode, (String, HintStyle)>,
+    /// Hint lines declared item by item, replacing `hint_order` in their modes.
+    pub layouts: HashMap<InputMode, Vec<LayoutItem>>,
+    /// Label of the hint for leaving a mode; "select" if unset.
+    pub select_label: Option<String>,
+    /// Key listed in that hint when several leave the mode; Enter if unset.
+    pub select_key: Option<KeyWithModifier>,
+    /// Shown in Locked mode instead of its hints, e.g. "🔒".
+    pub locked_indicator: Option<String>,
+    /// Shown as a badge before Tmux mode's hints while the prefix waits for a key, e.g. "PREFIX".
+    pub tmux_indicator: Option<String>,
+    /// Shown as a warning badge before the hints while the active tab is synced, e.g. "SYNC".
+    pub sync_indicator: Option<String>,
+    /// Show how many floating panes are open while they're visible, with the key hiding them.
+    pub floating_indicator: bool,
+}
+
+impl RenderOptions {
+    pub fn is_hidden(&self, mode: InputMode, name: &str) -> bool {
+        self.hidden_hints
+            .get(&mode)
+            .is_some_and(|hidden| hidden.iter().any(|hidden| hint_name_matches(hidden, name)))
+    }
+
+    pub fn max_hints_for(&self, mode: InputMode) -> usize {
+        self.mode_max_hints
+            .get(&mode)
+            .copied()
+            .unwrap_or(self.max_hints)
+    }
+}
+
+/// A hint for the keys bound to the given actions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomHint {
+    pub label: String,
+    pub actions: Vec<ActionPattern>,
+    pub matching: ActionMatch,
+    pub style: HintStyle,
+}
+
+/// Colors and weight a hint is drawn with instead of the theme's, e.g. a red "quit".
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HintStyle {
+    /// Text color of the key and the label.
+    pub fg: Option<Colour>,
+    /// Background of the key and the label.
+    pub bg: Option<Colour>,
+    pub bold: bool,
+}
+
+impl HintStyle {
+    /// `text` drawn in this style alone, as the layout's text segments are.
+    pub fn paint(&self, text: &str) -> StyledString {
+        Style {
+            foreground: self.fg,
+            background: self.bg,
+            is_bold: self.bold,
+            ..Style::new()
+        }
+        .paint(text.to_string())
+    }
+
+    pub fn apply(&self, parts: &mut [StyledString]) {
+        // Plain parts are the gaps between hints
+        for style in parts.iter_mut().map(|part| part.style_ref_mut()) {
+            if style.is_plain() {
+                continue;
+            }
+            style.foreground = self.fg.or(style.foreground);
+            style.background = self.bg.or(style.background);
+            style.is_bold |= self.bold;
+        }
+    }
+}
+
+/// Text attributes set for keys or labels instead of the built-in ones; unset ones are kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextAttributes {
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub dimmed: Option<bool>,
+    pub underline: Option<bool>,
+}
+
+impl TextAttributes {
+    pub fn apply(&self, mut style: Style) -> Style {
+        let attributes = [
+            (self.bold, &mut style.is_bold),
+            (self.italic, &mut style.is_italic),
+            (self.dimmed, &mut style.is_dimmed),
+            (self.underline, &mut style.is_underline),
+        ];
+        for (value, attribute) in attributes {
+            if let Some(value) = value {
+                *attribute = value;
+            }
+        }
+        style
+    }
+}
+
+/// How a key's actions have to match those of a custom hint.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ActionMatch {
+    /// The same actions, no more and no fewer.
+    #[default]
+    Exact,
+    /// The same actions, possibly followed by others, such as a `SwitchToMode` or `WriteChars`.
+    Prefix,
+}
+
+impl ActionMatch {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "exact" => Some(ActionMatch::Exact),
+            "prefix" => Some(ActionMatch::Prefix),
+            _ => None,
+        }
+    }
+
+    pub fn matches(self, key_actions: &[Action], patterns: &[ActionPattern]) -> bool {
+        let same_start = key_actions.len() >= patterns.len()
+            && key_actions
+                .iter()
+                .zip(patterns)
+                .all(|(action, pattern)| pattern.matches(action));
+        match self {
+            ActionMatch::Exact => same_start && key_actions.len() == patterns.len(),
+            ActionMatch::Prefix => same_start,
+        }
+    }
+}
+
+/// Whether `pattern` names the hint `name`, either exactly or as a prefix of its words.
+pub fn hint_name_matches(pattern: &str, name: &str) -> bool {
+    name.strip_prefix(pattern)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('_'))
+}
+
+/// What to do with hints whose actions would do nothing in the current session state.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ContextHints {
+    #[default]
+    Show,
+    Dim,
+    Hide,
+}
+
+impl ContextHints {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "show" => Some(ContextHints::Show),
+            "dim" => Some(ContextHints::Dim),
+            "hide" => Some(ContextHints::Hide),
+            _ => None,
+        }
+    }
+}
+
+/// Which of zellij's keybinding presets the built-in hints are tailored to.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Profile {
+    /// The classic preset, switching modes with Ctrl keys from Normal mode.
+    #[default]
+    ZellijDefault,
+    /// The "unlock-first" preset, where Ctrl g leaves the base Locked mode for a Normal mode of
+    /// single-letter mode switches: those are shown as keys, followed by the keys locking again.
+    UnlockFirst,
+    /// Only the mode switches most used from Normal mode.
+    Minimal,
+}
+
+impl Profile {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "zellij-default" | "default" => Some(Profile::ZellijDefault),
+            "unlock-first" => Some(Profile::UnlockFirst),
+            "minimal" => Some(Profile::Minimal),
+            _ => None,
+        }
+    }
+
+    /// Whether Normal mode lists the built-in hint called `name`.
+    pub fn lists(self, name: &str) -> bool {
+        match self {
+            Profile::Minimal => ["pane", "tab", "scroll", "quit"].contains(&name),
+            _ => true,
+        }
+    }
+}
+
+/// Which of Normal mode's mode switches is drawn in the selected ribbon colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HighlightMode {
+    #[default]
+    None,
+    /// The mode the user was in most recently, other than Normal and the base mode.
+    Last,
+    /// Always the same mode, e.g. the one used the most.
+    Favorite(InputMode),
+}
+
+impl HighlightMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "none" => Some(HighlightMode::None),
+            "last" => Some(HighlightMode::Last),
+            name => parse_mode(name).map(HighlightMode::Favorite),
+        }
+    }
+
+    /// The mode highlighted, given the last one used.
+    pub fn mode(self, last_mode: Option<InputMode>) -> Option<InputMode> {
+        match self {
+            HighlightMode::None => None,
+            HighlightMode::Last => last_mode,
+            HighlightMode::Favorite(mode) => Some(mode),
+        }
+    }
+}
+
+/// How much of each hint's label is shown.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Labels {
+    #[default]
+    Full,
+    /// Only the highlighted mnemonic letter, e.g. "n" for "new".
+    Minimal,
+}
+
+impl Labels {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "full" => Some(Labels::Full),
+            "minimal" => Some(Labels::Minimal),
+            _ => None,
+        }
+    }
+}
+
+/// Icons put before the labels of Normal mode's mode switches, e.g. "🪟 pane".
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum IconTheme {
+    #[default]
+    None,
+    /// Nerd Font glyphs, for terminals with a patched font.
+    Nerd,
+    /// Emoji, drawn two columns wide by most terminals.
+    Emoji,
+}
+
+impl IconTheme {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "none" => Some(IconTheme::None),
+            "nerd" => Some(IconTheme::Nerd),
+            "emoji" => Some(IconTheme::Emoji),
+            _ => None,
+        }
+    }
+
+    /// The icon of the hint called `name`, if it has one.
+    pub fn icon(self, name: &str) -> Option<&'static str> {
+        let (nerd, emoji) = match name {
+            "pane" => ("\u{f2d0}", "🪟"),
+            "tab" => ("\u{f04e9}", "📑"),
+            "resize" => ("\u{f0a68}", "📐"),
+            "move" => ("\u{f047}", "🔀"),
+            "scroll" => ("\u{f07d}", "📜"),
+            "search" => ("\u{f002}", "🔍"),
+            "session" => ("\u{f120}", "💼"),
+            "quit" => ("\u{f011}", "🚪"),
+            _ => return None,
+        };
+        match self {
+            IconTheme::None => None,
+            IconTheme::Nerd => Some(nerd),
+            IconTheme::Emoji => Some(emoji),
+        }
+    }
+}
+
+/// How the rendered hints are laid out on the output line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineOptions {
+    /// Maximum visible width of the line, padding included; 0 for no limit.
+    pub max_length: usize,
+    /// Added to the hints when they're cut to fit `max_length`.
+    pub overflow_str: String,
+    /// Per-mode overrides of `overflow_str`; an empty one cuts the hints without a marker.
+    pub mode_overflow_str: HashMap<InputMode, String>,
+    pub overflow_style: Style,
+    pub overflow_position: OverflowPosition,
+    /// Cut lines that are too long at the start rather than the end, as for reversed hints.
+    pub keep_end: bool,
+    /// Split hints that don't fit `max_length` into pages instead of cutting them off.
+    pub paging: bool,
+    /// Spaces before the hints.
+    pub padding_left: usize,
+    /// Spaces after the hints.
+    pub padding_right: usize,
+    /// Minimum visible width of the line; shorter lines are filled with spaces per `align`.
+    pub min_length: usize,
+    pub align: Align,
+}
+
+impl Default for LineOptions {
+    fn default() -> Self {
+        LineOptions {
+            max_length: 0,
+            overflow_str: "...".to_string(),
+            mode_overflow_str: HashMap::new(),
+            overflow_style: Style::new(),
+            overflow_position: OverflowPosition::default(),
+            keep_end: false,
+            paging: false,
+            padding_left: 1,
+            padding_right: 0,
+            min_length: 0,
+            align: Align::default(),
+        }
+    }
+}
+
+impl LineOptions {
+    /// These options with the overflow string of `mode`.
+    pub fn for_mode(&self, mode: InputMode) -> LineOptions {
+        let mut options = self.clone();
+        if let Some(overflow_str) = self.mode_overflow_str.get(&mode) {
+            options.overflow_str = overflow_str.clone();
+        }
+        options
+    }
+}
+
+/// Which side of the hints kept by truncation the overflow string goes on.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OverflowPosition {
+    Before,
+    #[default]
+    After,
+}
+
+impl OverflowPosition {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "before" => Some(OverflowPosition::Before),
+            "after" => Some(OverflowPosition::After),
+            _ => None,
+        }
+    }
+}
+
+/// How the hints are shown while in the base mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BaseModeDisplay {
+    #[default]
+    Full,
+    Dimmed,
+    Hidden,
+    /// Just the mode's name as a badge, e.g. "NORMAL", with the hints in the other modes.
+    Badge,
+}
+
+impl BaseModeDisplay {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "full" => Some(BaseModeDisplay::Full),
+            "dimmed" => Some(BaseModeDisplay::Dimmed),
+            "hidden" => Some(BaseModeDisplay::Hidden),
+            "badge" => Some(BaseModeDisplay::Badge),
+            _ => None,
+        }
+    }
+
+    /// How `mode` is shown: in full unless it's the base mode. The locked indicator is meant to
+    /// replace an empty bar, so it shows even when the base mode's hints are hidden or a badge.
+    pub fn in_mode(self, mode: InputMode, base_mode: InputMode, options: &RenderOptions) -> Self {
+        let shows_indicator = mode == InputMode::Locked && options.locked_indicator.is_some();
+        match self {
+            _ if mode != base_mode => BaseModeDisplay::Full,
+            BaseModeDisplay::Hidden | BaseModeDisplay::Badge if shows_indicator => {
+                BaseModeDisplay::Full
+            }
+            display => display,
+        }
+    }
+}
+
+/// How the plugin draws the hints in its own pane. The zjstatus pipe always gets ANSI.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RenderMode {
+    #[default]
+    Ansi,
+    /// Zellij's ribbon components, themed by zellij itself.
+    Components,
+}
+
+impl RenderMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "ansi" => Some(RenderMode::Ansi),
+            "components" => Some(RenderMode::Components),
+            _ => None,
+        }
+    }
+}
+
+/// Where the hints sit within a line wider than they are.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "left" => Some(Align::Left),
+            "center" => Some(Align::Center),
+            "right" => Some(Align::Right),
+            _ => None,
+        }
+    }
+}
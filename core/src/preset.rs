@@ -0,0 +1,56 @@
+//! Named bundles of option values, picked with the `style` option. Every option set alongside
+//! it still overrides the preset's value.
+
+use crate::options::{ContextHints, KeysPerAction, Labels, LineOptions, RenderOptions};
+
+/// The options a named style starts from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preset {
+    pub render: RenderOptions,
+    pub line: LineOptions,
+    /// Whether the hints keep their theme colors; without them they're plain text.
+    pub colors: bool,
+}
+
+impl Default for Preset {
+    fn default() -> Self {
+        Preset {
+            render: RenderOptions::default(),
+            line: LineOptions::default(),
+            colors: true,
+        }
+    }
+}
+
+impl Preset {
+    /// The preset called `name`: "default", "compact", "verbose", "minimal" or "plain".
+    pub fn parse(name: &str) -> Option<Self> {
+        let mut preset = Preset::default();
+        match name.trim().to_lowercase().as_str() {
+            "default" => {}
+            // One key per hint and nothing that wouldn't do anything
+            "compact" => {
+                preset.render.keys.per_action = KeysPerAction::Limit(1);
+                preset.render.context_hints = ContextHints::Hide;
+                preset.line.padding_left = 0;
+            }
+            // Every hint, including those without keys, set apart from each other
+            "verbose" => {
+                preset.render.show_missing = true;
+                preset.render.context_hints = ContextHints::Dim;
+                preset.render.separator = " │ ".to_string();
+            }
+            // Compact, with labels cut down to their mnemonic letter
+            "minimal" => {
+                preset.render.keys.per_action = KeysPerAction::Limit(1);
+                preset.render.context_hints = ContextHints::Hide;
+                preset.render.labels = Labels::Minimal;
+                preset.line.padding_left = 0;
+            }
+            // The default hints without their colors
+            "plain" => preset.colors = false,
+            _ => return None,
+        }
+        Some(preset)
+    }
+}
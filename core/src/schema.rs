@@ -0,0 +1,201 @@
+//! Every option the plugin reads, with the kind of value it takes and its default, so the
+//! plugin can list its own settings, e.g.
+//!
+//! ```kdl
+//! // max_length: number, default: 0
+//! overflow_str "…" // text, default: "..."
+//! // <mode>_hide_<hint>: bool, default: false
+//! normal_hide_quit "true"
+//! ```
+
+use crate::mode::parse_mode;
+use std::collections::BTreeMap;
+
+/// An option, or a family of them with `<placeholders>` in their name such as `theme_<name>`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OptionInfo {
+    pub key: &'static str,
+    /// The kind of value, e.g. "bool", "number" or the choices of an enum.
+    pub kind: &'static str,
+    pub default: &'static str,
+}
+
+const fn option(key: &'static str, kind: &'static str, default: &'static str) -> OptionInfo {
+    OptionInfo { key, kind, default }
+}
+
+/// The options in the order the README lists them.
+pub const OPTIONS: &[OptionInfo] = &[
+    option("style", "default|compact|verbose|minimal|plain", "default"),
+    option(
+        "profile",
+        "zellij-default|unlock-first|minimal",
+        "zellij-default",
+    ),
+    option("colors", "bool", "true"),
+    option("max_length", "number", "0"),
+    option("overflow_str", "text", "\"...\""),
+    option("overflow_str_<mode>", "text", "overflow_str"),
+    option("overflow_color", "color", "unset"),
+    option("overflow_bold", "bool", "false"),
+    option("overflow_position", "before|after", "after"),
+    option("paging", "bool", "false"),
+    option("padding_left", "number", "1"),
+    option("padding_right", "number", "0"),
+    option("min_length", "number", "0"),
+    option("align", "left|center|right", "left"),
+    option("format", "template", "unset"),
+    option("pipe_name", "text", "zjstatus_hints"),
+    option("pipe_output", "bool", "true"),
+    option("require_zjstatus", "bool", "true"),
+    option("pipe_on_request", "bool", "false"),
+    option("print_to_pane", "bool", "true"),
+    option("hide_in_base_mode", "bool", "false"),
+    option("hide_in_text_entry", "bool", "false"),
+    option("base_mode_display", "full|dimmed|badge|hidden", "full"),
+    option("locked_indicator", "text", "unset"),
+    option("locked_show_bindings", "bool", "false"),
+    option("sync_indicator", "text", "\"SYNC\""),
+    option("floating_indicator", "bool", "false"),
+    option("tmux_indicator", "text", "unset"),
+    option("keys_per_action", "all|first|number", "all"),
+    option("key_order", "letters|arrows|keymap", "letters"),
+    option("modifier_order", "modifiers", "ctrl, alt, shift, super"),
+    option("super_label", "super|glyph|win|cmd|meta", "super"),
+    option("key_names", "<key>=<name>, ...", "unset"),
+    option("only_modifiers", "modifiers", "unset"),
+    option("exclude_modifiers", "modifiers", "unset"),
+    option("show_missing", "bool", "false"),
+    option("context_hints", "show|dim|hide", "show"),
+    option("labels", "full|minimal", "full"),
+    option("icon_theme", "none|nerd|emoji", "none"),
+    option("strict_mnemonics", "bool", "false"),
+    option("key_bold", "bool", "true"),
+    option("key_italic", "bool", "false"),
+    option("key_dim", "bool", "false"),
+    option("key_underline", "bool", "false"),
+    option("label_bold", "bool", "false"),
+    option("label_italic", "bool", "false"),
+    option("label_dim", "bool", "false"),
+    option("label_underline", "bool", "false"),
+    option("key_caps", "rounded|slanted|two characters", "unset"),
+    option("key_caps_bg", "color", "unset"),
+    option("ribbons", "bool", "false"),
+    option("highlight_mode", "none|last|mode", "none"),
+    option("separator", "text", "\"\""),
+    option("max_hints", "number", "0"),
+    option("<mode>_max_hints", "number", "max_hints"),
+    option("reverse", "bool", "false"),
+    option("quick_keys", "bool", "false"),
+    option("separate_splits", "bool", "false"),
+    option("merge_pages", "bool", "false"),
+    option("<mode>_hide", "hint names", "unset"),
+    option("<mode>_hide_<hint>", "bool", "false"),
+    option("<mode>_hints", "hint names", "unset"),
+    option("<mode>_hint_<label>", "actions", "unset"),
+    option("<mode>_hint_<label>_match", "exact|prefix", "exact"),
+    option("<mode>_hint_<label>_fg", "color", "unset"),
+    option("<mode>_hint_<label>_bg", "color", "unset"),
+    option("<mode>_hint_<label>_bold", "bool", "false"),
+    option("<mode>_layout", "kdl", "unset"),
+    option("<mode>_prefix", "text", "unset"),
+    option("<mode>_suffix", "text", "unset"),
+    option("<mode>_prefix_fg", "color", "unset"),
+    option("<mode>_prefix_bg", "color", "unset"),
+    option("<mode>_prefix_bold", "bool", "false"),
+    option("<mode>_suffix_fg", "color", "unset"),
+    option("<mode>_suffix_bg", "color", "unset"),
+    option("<mode>_suffix_bold", "bool", "false"),
+    option("select_label", "text", "select"),
+    option("select_key", "key", "unset"),
+    option("show_other_clients", "bool", "false"),
+    option("pipe_interval_ms", "number", "0"),
+    option("show_delay_ms", "number", "0"),
+    option("auto_hide_after", "seconds", "0"),
+    option("flash_ms", "number", "0"),
+    option("theme_<name>", "<slot>=<color>, ...", "unset"),
+    option("theme", "name", "unset"),
+    option("debug", "bool", "false"),
+    option("debug_file", "path", "unset"),
+    option("verbose_pipe_name", "text", "unset"),
+    option("extra_pipe_<name>", "<setting>=<value>, ...", "unset"),
+    option("width_pipe_name", "text", "unset"),
+    option("render_mode", "ansi|components", "ansi"),
+    option("mouse_scroll", "bool", "false"),
+];
+
+/// The default of the bool option `key`, as listed in `OPTIONS`.
+pub fn default_flag(key: &str) -> bool {
+    option_for_key(key).is_some_and(|option| option.default == "true")
+}
+
+/// Whether `key` is one of the options `pattern` names, where a placeholder stands for any
+/// non-empty text and `<mode>` for the name of a mode.
+pub fn matches_pattern(pattern: &str, key: &str) -> bool {
+    let Some((literal, rest)) = pattern.split_once('<') else {
+        return pattern == key;
+    };
+    let (Some(key), Some((placeholder, rest))) = (key.strip_prefix(literal), rest.split_once('>'))
+    else {
+        return false;
+    };
+    (1..=key.len())
+        .filter(|&end| key.is_char_boundary(end))
+        .any(|end| {
+            let (value, tail) = key.split_at(end);
+            (placeholder != "mode" || parse_mode(value).is_some()) && matches_pattern(rest, tail)
+        })
+}
+
+/// The option `key` is one of, preferring the most specific family, e.g. `<mode>_max_hints`
+/// over `<mode>_hints` for `pane_max_hints`.
+pub fn option_for_key(key: &str) -> Option<&'static OptionInfo> {
+    OPTIONS
+        .iter()
+        .filter(|option| matches_pattern(option.key, key))
+        .max_by_key(|option| {
+            option
+                .key
+                .split(['<', '>'])
+                .step_by(2)
+                .map(str::len)
+                .sum::<usize>()
+        })
+}
+
+/// Every option with its kind, default and configured value, as KDL that can be pasted into the
+/// plugin's configuration: configured options as nodes, the rest as comments. Configured keys
+/// that aren't options, e.g. misspelled ones, come last.
+pub fn dump_config(configuration: &BTreeMap<String, String>) -> String {
+    let mut families: BTreeMap<&str, Vec<(&String, &String)>> = BTreeMap::new();
+    let mut unknown = vec![];
+    for (key, value) in configuration {
+        match option_for_key(key) {
+            Some(option) => families.entry(option.key).or_default().push((key, value)),
+            None => unknown.push((key, value)),
+        }
+    }
+    let mut lines = vec![];
+    for option in OPTIONS {
+        let details = format!("{}, default: {}", option.kind, option.default);
+        match families.get(option.key) {
+            Some(set) if !option.key.contains('<') => {
+                let (key, value) = set[0];
+                lines.push(format!("{} {:?} // {}", key, value, details));
+            }
+            set => {
+                lines.push(format!("// {}: {}", option.key, details));
+                for (key, value) in set.into_iter().flatten() {
+                    lines.push(format!("{} {:?}", key, value));
+                }
+            }
+        }
+    }
+    if !unknown.is_empty() {
+        lines.push("// unknown options".to_string());
+        for (key, value) in unknown {
+            lines.push(format!("{} {:?}", key, value));
+        }
+    }
+    lines.join("\n") + "\n"
+}
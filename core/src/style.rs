@@ -0,0 +1,183 @@
+//! A minimal SGR styler covering just what the hints need: colours, bold/dim/italic/underline,
+//! and owned text.
+
+use std::fmt;
+
+// Variant names match what `palette_match!` expands to.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Colour {
+    Fixed(u8),
+    RGB(u8, u8, u8),
+}
+
+impl Colour {
+    /// A colour as written in the configuration: a basic colour name ("red"), a 256-colour
+    /// palette index ("208"), or a hex RGB value ("#ff8700").
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        if let Some(hex) = value.strip_prefix('#') {
+            let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+            return match hex.len() {
+                6 => Some(Colour::RGB(channel(0)?, channel(2)?, channel(4)?)),
+                _ => None,
+            };
+        }
+        let basic = [
+            "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+        ];
+        match basic.iter().position(|name| *name == value) {
+            Some(n) => Some(Colour::Fixed(n as u8)),
+            None => value.parse().ok().map(Colour::Fixed),
+        }
+    }
+
+    fn sgr(self, base: u8) -> String {
+        match self {
+            Colour::Fixed(n) => format!("{};5;{}", base, n),
+            Colour::RGB(r, g, b) => format!("{};2;{};{};{}", base, r, g, b),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    pub foreground: Option<Colour>,
+    pub background: Option<Colour>,
+    pub is_bold: bool,
+    pub is_dimmed: bool,
+    pub is_italic: bool,
+    pub is_underline: bool,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    pub fn fg(mut self, colour: Colour) -> Self {
+        self.foreground = Some(colour);
+        self
+    }
+
+    pub fn on(mut self, colour: Colour) -> Self {
+        self.background = Some(colour);
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.is_bold = true;
+        self
+    }
+
+    pub fn dimmed(mut self) -> Self {
+        self.is_dimmed = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.is_italic = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.is_underline = true;
+        self
+    }
+
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+
+    pub fn paint(self, text: impl Into<String>) -> StyledString {
+        StyledString {
+            style: self,
+            text: text.into(),
+        }
+    }
+
+    /// The escape sequence that switches the terminal to this style, if any.
+    pub fn prefix(&self) -> String {
+        if self.is_plain() {
+            return String::new();
+        }
+        let mut codes = vec![];
+        if self.is_bold {
+            codes.push("1".to_string());
+        }
+        if self.is_dimmed {
+            codes.push("2".to_string());
+        }
+        if self.is_italic {
+            codes.push("3".to_string());
+        }
+        if self.is_underline {
+            codes.push("4".to_string());
+        }
+        if let Some(colour) = self.foreground {
+            codes.push(colour.sgr(38));
+        }
+        if let Some(colour) = self.background {
+            codes.push(colour.sgr(48));
+        }
+        format!("\x1b[{}m", codes.join(";"))
+    }
+
+    /// The escape sequence that resets the terminal after text in this style, if any.
+    pub fn suffix(&self) -> &'static str {
+        if self.is_plain() {
+            ""
+        } else {
+            "\x1b[0m"
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StyledString {
+    style: Style,
+    text: String,
+}
+
+impl StyledString {
+    pub fn style_ref_mut(&mut self) -> &mut Style {
+        &mut self.style
+    }
+
+    pub fn text_mut(&mut self) -> &mut String {
+        &mut self.text
+    }
+}
+
+impl fmt::Display for StyledString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            self.style.prefix(),
+            self.text,
+            self.style.suffix()
+        )
+    }
+}
+
+/// Displays a sequence of styled strings back to back, only emitting escape sequences where the
+/// style actually changes so runs of identically styled parts share a single prefix.
+pub struct StyledStrings<'a>(pub &'a [StyledString]);
+
+impl fmt::Display for StyledStrings<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut current = Style::default();
+        for part in self.0 {
+            if part.text.is_empty() {
+                continue;
+            }
+            if part.style != current {
+                write!(f, "{}{}", current.suffix(), part.style.prefix())?;
+                current = part.style;
+            }
+            write!(f, "{}", part.text)?;
+        }
+        write!(f, "{}", current.suffix())
+    }
+}
@@ -0,0 +1,470 @@
+//! The built-in hints of each mode as data, read by a single loop in `collect_hints_for_mode`:
+//! where each hint finds its keys, its label, what kind of hint it is and how long it holds out
+//! when the line is too short. Adding one is adding a row. Normal mode, whose mode switches share
+//! a modifier, is assembled there separately, with priorities of its own.
+
+use crate::actions::{variant_name, ActionPattern};
+use crate::keys::{
+    find_keys_for_action_groups, find_keys_for_actions, find_keys_matching, plugin_keys, TO_NORMAL,
+};
+use crate::mode::takes_text;
+use crate::options::{ActionMatch, KeyOptions};
+use zellij_tile::prelude::actions::{Action, SearchDirection};
+use zellij_tile::prelude::*;
+
+const PLUGIN_SESSION_MANAGER: &str = "session-manager";
+const PLUGIN_CONFIGURATION: &str = "configuration";
+const PLUGIN_MANAGER: &str = "plugin-manager";
+const PLUGIN_ABOUT: &str = "zellij:about";
+
+/// Where a hint's keys come from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HintKeys {
+    /// Keys whose actions start like these, e.g. a split followed by a return to Normal mode.
+    Starting(Vec<Action>),
+    /// Keys bound to these actions and nothing else.
+    Exactly(Vec<Action>),
+    /// Keys bound to exactly these actions without a modifier, leaving out the ones that usually
+    /// do the same from every mode and that Normal mode already lists.
+    Unmodified(Vec<Action>),
+    /// Keys bound to any of the groups, shown together, e.g. one per direction.
+    Groups(Vec<Vec<Action>>),
+    /// Like `Groups`, but keys whose actions start like a group, as for `Starting`.
+    StartingGroups(Vec<Vec<Action>>),
+    /// Like `Groups`, but just ← and → when both are among them, as for moving between tabs.
+    Sideways(Vec<Vec<Action>>),
+    /// Keys bound to an action of this name, whatever its arguments.
+    Named(&'static str),
+    /// Keys launching or focusing this plugin.
+    Plugin(&'static str),
+    /// The key for leaving the mode, as `select_key` picks it; labeled by `select_label`.
+    Select,
+    /// The keys back to the base mode, or out of it, as modes without hints of their own show.
+    Return,
+    /// Esc, when it undoes the text typed so far or just switches modes, as in the rename modes.
+    Cancel,
+}
+
+impl HintKeys {
+    /// The keys found in `keymap`, for all but `Select` and `Return`, which depend on more than
+    /// the keymap.
+    pub fn find(
+        &self,
+        keymap: &[(KeyWithModifier, Vec<Action>)],
+        key_options: &KeyOptions,
+    ) -> Vec<KeyWithModifier> {
+        let groups = |groups: &[Vec<Action>]| {
+            let groups: Vec<&[Action]> = groups.iter().map(Vec::as_slice).collect();
+            find_keys_for_action_groups(keymap, &groups, key_options)
+        };
+        match self {
+            HintKeys::Starting(actions) => {
+                find_keys_for_actions(keymap, actions, false, key_options)
+            }
+            HintKeys::Exactly(actions) => find_keys_for_actions(keymap, actions, true, key_options),
+            HintKeys::Unmodified(actions) => {
+                find_keys_for_actions(keymap, actions, true, key_options)
+                    .into_iter()
+                    .filter(|key| key.key_modifiers.is_empty())
+                    .collect()
+            }
+            HintKeys::Groups(actions) => groups(actions),
+            HintKeys::StartingGroups(actions) => {
+                let mut keys: Vec<KeyWithModifier> = actions
+                    .iter()
+                    .flat_map(|actions| find_keys_for_actions(keymap, actions, false, key_options))
+                    .collect();
+                key_options.sort_group(&mut keys);
+                keys
+            }
+            HintKeys::Sideways(actions) => {
+                let keys = groups(actions);
+                let sideways = [
+                    KeyWithModifier::new(BareKey::Left),
+                    KeyWithModifier::new(BareKey::Right),
+                ];
+                if sideways.iter().all(|key| keys.contains(key)) {
+                    sideways.to_vec()
+                } else {
+                    keys
+                }
+            }
+            HintKeys::Named(name) => {
+                let patterns = [ActionPattern::Any(name.to_string())];
+                find_keys_matching(keymap, &patterns, ActionMatch::Prefix, key_options)
+            }
+            HintKeys::Plugin(plugin) => plugin_keys(keymap, plugin, key_options),
+            HintKeys::Cancel => {
+                let esc = KeyWithModifier::new(BareKey::Esc);
+                let cancels = keymap.iter().any(|(key, actions)| {
+                    *key == esc
+                        && match actions.as_slice() {
+                            [Action::SwitchToMode(_)] => true,
+                            [first, ..] => variant_name(first).starts_with("Undo"),
+                            [] => false,
+                        }
+                });
+                key_options.apply(if cancels { vec![esc] } else { vec![] })
+            }
+            HintKeys::Select | HintKeys::Return => vec![],
+        }
+    }
+
+    /// The action whose effect decides whether the hint would do anything right now.
+    pub fn first_action(&self) -> Option<&Action> {
+        match self {
+            HintKeys::Starting(actions)
+            | HintKeys::Exactly(actions)
+            | HintKeys::Unmodified(actions) => actions.first(),
+            HintKeys::Groups(groups)
+            | HintKeys::StartingGroups(groups)
+            | HintKeys::Sideways(groups) => groups.first().and_then(|actions| actions.first()),
+            _ => None,
+        }
+    }
+}
+
+/// What a hint does, which decides how the session's state bears on it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HintCategory {
+    /// Changes the session, e.g. closing a pane; dimmed or hidden per `context_hints` when it
+    /// would do nothing.
+    Command,
+    /// Moves the focus or the view; also subject to `context_hints`.
+    Navigation,
+    /// Enters another mode or opens a plugin.
+    Switch,
+    /// Leaves the mode; custom hints go before it.
+    Leave,
+}
+
+impl HintCategory {
+    /// Whether the hint is dimmed or hidden when it would do nothing.
+    pub fn is_contextual(self) -> bool {
+        matches!(self, HintCategory::Command | HintCategory::Navigation)
+    }
+}
+
+/// Which hints `fit_hints` leaves out first when they don't all fit: the lowest, and of equal
+/// ones the last (or with `keep_end`, the first).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Rarely used, e.g. breaking a pane out to a neighbouring tab.
+    Low,
+    #[default]
+    Normal,
+    /// The way out of the mode, and badges such as the locked indicator.
+    High,
+}
+
+/// One row of the table: a built-in hint of a mode.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HintSpec {
+    pub keys: HintKeys,
+    pub label: &'static str,
+    pub category: HintCategory,
+    pub priority: Priority,
+}
+
+impl HintSpec {
+    fn priority(self, priority: Priority) -> Self {
+        HintSpec { priority, ..self }
+    }
+}
+
+fn hint(keys: HintKeys, label: &'static str, category: HintCategory) -> HintSpec {
+    HintSpec {
+        keys,
+        label,
+        category,
+        priority: Priority::Normal,
+    }
+}
+
+/// The action, then a return to Normal mode.
+fn then_normal(action: Action) -> HintKeys {
+    HintKeys::Starting(vec![action, TO_NORMAL])
+}
+
+/// One group per direction, in the order h, j, k, l.
+fn directions(action: fn(Direction) -> Action) -> HintKeys {
+    HintKeys::Groups(
+        [
+            Direction::Left,
+            Direction::Down,
+            Direction::Up,
+            Direction::Right,
+        ]
+        .into_iter()
+        .map(|direction| vec![action(direction)])
+        .collect(),
+    )
+}
+
+fn pair(first: Action, second: Action) -> HintKeys {
+    HintKeys::Groups(vec![vec![first], vec![second]])
+}
+
+/// The hints listed while scrolling, and while searching before the search's own.
+fn scroll_hints() -> Vec<HintSpec> {
+    use HintCategory::{Navigation, Switch};
+    vec![
+        hint(
+            HintKeys::Exactly(vec![
+                Action::SwitchToMode(InputMode::EnterSearch),
+                Action::SearchInput(vec![0]),
+            ]),
+            "search",
+            Switch,
+        ),
+        hint(
+            pair(Action::ScrollDown, Action::ScrollUp),
+            "scroll",
+            Navigation,
+        ),
+        hint(
+            pair(Action::PageScrollDown, Action::PageScrollUp),
+            "page",
+            Navigation,
+        ),
+        hint(
+            pair(Action::HalfPageScrollDown, Action::HalfPageScrollUp),
+            "half page",
+            Navigation,
+        ),
+    ]
+}
+
+/// Pane mode's split into one direction, then a return to Normal mode.
+/// Replaces the "→" and "↓" split hints with a single "split →↓" when both are bound to keys
+/// with the same modifiers, e.g. "r" and "d", listing the keys for the right first.
+pub fn group_splits(
+    hints: &mut Vec<HintSpec>,
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    key_options: &KeyOptions,
+) {
+    let (Some(right), Some(down)) = (
+        hints.iter().position(|hint| hint.label == "→"),
+        hints.iter().position(|hint| hint.label == "↓"),
+    ) else {
+        return;
+    };
+    let (HintKeys::Starting(right_actions), HintKeys::Starting(down_actions)) =
+        (&hints[right].keys, &hints[down].keys)
+    else {
+        return;
+    };
+    let right_keys = hints[right].keys.find(keymap, key_options);
+    let down_keys = hints[down].keys.find(keymap, key_options);
+    let related = match (right_keys.first(), down_keys.first()) {
+        (Some(right), Some(down)) => right.key_modifiers == down.key_modifiers,
+        _ => false,
+    };
+    if related {
+        let groups = vec![right_actions.clone(), down_actions.clone()];
+        hints[right] = hint(
+            HintKeys::StartingGroups(groups),
+            "split →↓",
+            HintCategory::Command,
+        );
+        hints.remove(down);
+    }
+}
+
+/// Replaces the "page" and "half page" scroll hints with a single "page/½page" showing the keys
+/// of both.
+pub fn merge_pages(hints: &mut Vec<HintSpec>) {
+    let (Some(page), Some(half_page)) = (
+        hints.iter().position(|hint| hint.label == "page"),
+        hints.iter().position(|hint| hint.label == "half page"),
+    ) else {
+        return;
+    };
+    let (HintKeys::Groups(pages), HintKeys::Groups(half_pages)) =
+        (&hints[page].keys, &hints[half_page].keys)
+    else {
+        return;
+    };
+    let groups = pages.iter().chain(half_pages).cloned().collect();
+    hints[page] = hint(
+        HintKeys::Groups(groups),
+        "page/½page",
+        HintCategory::Navigation,
+    );
+    hints.remove(half_page);
+}
+
+/// The built-in hints of `mode` in the order they are listed, all but Normal mode's.
+pub fn mode_hints(mode: InputMode) -> Vec<HintSpec> {
+    use HintCategory::{Command, Leave, Navigation, Switch};
+    use Priority::{High, Low};
+    let select = hint(HintKeys::Select, "select", Leave).priority(High);
+    let mut hints = match mode {
+        InputMode::Normal => vec![],
+        InputMode::Pane => vec![
+            hint(
+                then_normal(Action::NewPane(None, None, false)),
+                "new",
+                Command,
+            ),
+            hint(then_normal(Action::CloseFocus), "x", Command),
+            hint(then_normal(Action::ToggleFocusFullscreen), "full", Command),
+            hint(then_normal(Action::ToggleFloatingPanes), "float", Command),
+            hint(
+                then_normal(Action::NewPane(Some(Direction::Right), None, false)),
+                "→",
+                Command,
+            ),
+            hint(
+                then_normal(Action::NewPane(Some(Direction::Down), None, false)),
+                "↓",
+                Command,
+            ),
+            hint(then_normal(Action::BreakPaneLeft), "break ←", Command).priority(Low),
+            hint(then_normal(Action::BreakPaneRight), "break →", Command).priority(Low),
+            hint(
+                HintKeys::Starting(vec![
+                    Action::SwitchToMode(InputMode::RenamePane),
+                    Action::PaneNameInput(vec![0]),
+                ]),
+                "rename",
+                Switch,
+            ),
+            hint(directions(Action::MoveFocus), "move", Navigation),
+            hint(
+                HintKeys::Starting(vec![Action::FocusNextPane]),
+                "next",
+                Navigation,
+            ),
+            hint(
+                HintKeys::Starting(vec![Action::FocusPreviousPane]),
+                "prev",
+                Navigation,
+            ),
+            select,
+        ],
+        InputMode::Tab => vec![
+            hint(
+                then_normal(Action::NewTab(None, vec![], None, None, None, true)),
+                "new",
+                Command,
+            ),
+            hint(then_normal(Action::CloseTab), "close", Command),
+            hint(then_normal(Action::BreakPane), "break pane", Command).priority(Low),
+            hint(then_normal(Action::BreakPaneLeft), "break ←", Command).priority(Low),
+            hint(then_normal(Action::BreakPaneRight), "break →", Command).priority(Low),
+            hint(then_normal(Action::ToggleActiveSyncTab), "sync", Command),
+            hint(
+                HintKeys::Starting(vec![
+                    Action::SwitchToMode(InputMode::RenameTab),
+                    Action::TabNameInput(vec![0]),
+                ]),
+                "rename",
+                Switch,
+            ),
+            hint(
+                HintKeys::Sideways(vec![
+                    vec![Action::GoToPreviousTab],
+                    vec![Action::GoToNextTab],
+                ]),
+                "move",
+                Navigation,
+            ),
+            select,
+        ],
+        InputMode::Resize => vec![
+            hint(
+                pair(
+                    Action::Resize(Resize::Increase, None),
+                    Action::Resize(Resize::Decrease, None),
+                ),
+                "resize",
+                Command,
+            ),
+            hint(
+                directions(|direction| Action::Resize(Resize::Increase, Some(direction))),
+                "increase",
+                Command,
+            ),
+            hint(
+                directions(|direction| Action::Resize(Resize::Decrease, Some(direction))),
+                "decrease",
+                Command,
+            ),
+            select,
+        ],
+        InputMode::Move => vec![
+            hint(
+                directions(|direction| Action::MovePane(Some(direction))),
+                "move",
+                Command,
+            ),
+            select,
+        ],
+        InputMode::Scroll => {
+            let mut hints = scroll_hints();
+            hints.push(select);
+            hints
+        }
+        InputMode::Search => {
+            let mut hints = scroll_hints();
+            hints.extend([
+                hint(
+                    HintKeys::Exactly(vec![Action::Search(SearchDirection::Down)]),
+                    "down",
+                    Navigation,
+                ),
+                hint(
+                    HintKeys::Exactly(vec![Action::Search(SearchDirection::Up)]),
+                    "up",
+                    Navigation,
+                ),
+                select,
+            ]);
+            hints
+        }
+        InputMode::Session => vec![
+            hint(HintKeys::Exactly(vec![Action::Detach]), "detach", Command),
+            // Quitting ends the session for every client, unlike detaching
+            hint(HintKeys::Unmodified(vec![Action::Quit]), "kill", Command),
+            hint(HintKeys::Named("RenameSession"), "rename", Switch),
+            hint(HintKeys::Plugin(PLUGIN_SESSION_MANAGER), "manager", Switch),
+            hint(HintKeys::Plugin(PLUGIN_CONFIGURATION), "config", Switch),
+            hint(HintKeys::Plugin(PLUGIN_MANAGER), "plugins", Switch).priority(Low),
+            hint(HintKeys::Plugin(PLUGIN_ABOUT), "about", Switch).priority(Low),
+            select,
+        ],
+        // Esc cancels the text typed so far, unlike the keys confirming it
+        mode if takes_text(mode) => vec![
+            hint(HintKeys::Return, "", Leave).priority(High),
+            hint(HintKeys::Cancel, "cancel", Leave).priority(High),
+        ],
+        _ => vec![hint(HintKeys::Return, "", Leave).priority(High)],
+    };
+    // Editing the scrollback is bound in Scroll mode by default, but some configs bind it in
+    // Pane mode or elsewhere, so every mode with hints of its own looks for it, as for clearing
+    // the screen (`Clear` in a keybind) and toggling pane frames or mouse mode. They're off the
+    // mode's beaten path, so they make way first.
+    if let Some(at) = hints.iter().position(|hint| hint.keys == HintKeys::Select) {
+        let extras = [
+            hint(then_normal(Action::EditScrollback), "edit", Switch),
+            hint(
+                HintKeys::Starting(vec![Action::ClearScreen]),
+                "clear",
+                Command,
+            ),
+            hint(
+                HintKeys::Starting(vec![Action::TogglePaneFrames]),
+                "frames",
+                Command,
+            ),
+            hint(
+                HintKeys::Starting(vec![Action::ToggleMouseMode]),
+                "mouse",
+                Command,
+            ),
+        ];
+        hints.splice(at..at, extras.map(|extra| extra.priority(Low)));
+    }
+    hints
+}
@@ -0,0 +1,65 @@
+//! Regression cases for escape sequences the parser used to mishandle.
+
+use crate::ansi::{calculate_visible_length, skip_columns, truncate_ansi_string};
+
+#[test]
+fn sgr_sequences_are_zero_width() {
+    assert_eq!(calculate_visible_length("\x1b[1;38;5;4mab\x1b[0m"), 2);
+}
+
+#[test]
+fn non_sgr_csi_ends_at_its_final_byte() {
+    // Used to swallow everything up to the next 'm'.
+    assert_eq!(calculate_visible_length("\x1b[2Kabc\x1b[?25lde"), 5);
+}
+
+#[test]
+fn osc_runs_until_its_terminator() {
+    assert_eq!(calculate_visible_length("\x1b]0;title\x07ab"), 2);
+    assert_eq!(
+        calculate_visible_length("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\"),
+        4
+    );
+}
+
+#[test]
+fn c1_controls_are_zero_width() {
+    assert_eq!(
+        calculate_visible_length("\u{9b}1mab\u{9d}x\u{9c}c\u{85}"),
+        3
+    );
+}
+
+#[test]
+fn unterminated_sequences_do_not_count() {
+    assert_eq!(calculate_visible_length("ab\x1b"), 2);
+    assert_eq!(calculate_visible_length("ab\x1b[1;2"), 2);
+    assert_eq!(calculate_visible_length("ab\x1b]0;never ends"), 2);
+}
+
+#[test]
+fn interrupted_csi_leaves_the_interrupting_character_visible() {
+    assert_eq!(calculate_visible_length("\x1b[1…"), 1);
+}
+
+#[test]
+fn truncation_measures_the_overflow_string_in_characters() {
+    let truncated = truncate_ansi_string("abcdefgh", "…", 4);
+    assert_eq!(truncated, "abc…");
+    assert_eq!(calculate_visible_length(&truncated), 4);
+}
+
+#[test]
+fn wide_characters_count_as_two_columns() {
+    assert_eq!(calculate_visible_length("\x1b[1m🔒\x1b[0m ab"), 5);
+    let truncated = truncate_ansi_string("🔒🔒ab", "…", 4);
+    assert_eq!(truncated, "🔒…");
+    assert_eq!(calculate_visible_length(&truncated), 3);
+}
+
+#[test]
+fn skipping_columns_keeps_the_styles() {
+    assert_eq!(skip_columns("\x1b[1mab\x1b[0mcd", 3), "\x1b[1m\x1b[0md");
+    assert_eq!(skip_columns("🔒ab", 1), " ab");
+    assert_eq!(skip_columns("ab", 5), "");
+}
@@ -0,0 +1,74 @@
+use crate::config::{base_mode_display, colors, line_options, render_options};
+use crate::{BaseModeDisplay, ContextHints, KeysPerAction, RenderOptions};
+use std::collections::BTreeMap;
+use zellij_tile::prelude::*;
+
+fn configuration(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn options_set_alongside_a_style_override_it() {
+    let compact = configuration(&[("style", "compact"), ("context_hints", "dim")]);
+    let options = render_options(&compact);
+    assert_eq!(options.keys.per_action, KeysPerAction::Limit(1));
+    assert_eq!(options.context_hints, ContextHints::Dim);
+    assert_eq!(line_options(&compact).padding_left, 0);
+    assert!(!colors(&configuration(&[("style", "plain")])));
+    assert!(colors(&configuration(&[
+        ("style", "plain"),
+        ("colors", "true")
+    ])));
+    // Nothing configured is the same as the defaults
+    let options = render_options(&BTreeMap::new());
+    assert_eq!(options.separator, RenderOptions::default().separator);
+    assert_eq!(options.max_hints, RenderOptions::default().max_hints);
+}
+
+#[test]
+fn hint_families_are_collected_per_mode() {
+    let options = render_options(&configuration(&[
+        ("pane_hide", "rename, frames"),
+        ("pane_hide_select", "true"),
+        ("tab_hide_sync", "false"),
+        ("tab_hint_sync_all", "ToggleActiveSyncTab"),
+        ("tab_hint_sync_all_bold", "true"),
+        ("reverse", "true"),
+    ]));
+    assert_eq!(
+        options.hidden_hints.get(&InputMode::Pane),
+        Some(&vec![
+            "rename".to_string(),
+            "frames".to_string(),
+            "select".to_string()
+        ])
+    );
+    assert_eq!(options.hidden_hints.get(&InputMode::Tab), None);
+    let custom = &options.custom_hints[&InputMode::Tab];
+    assert_eq!(custom.len(), 1);
+    assert_eq!(custom[0].label, "sync all");
+    assert!(custom[0].style.bold);
+    // Reversed hints keep their end when truncated
+    assert!(options.reverse);
+    assert!(line_options(&configuration(&[("reverse", "true")])).keep_end);
+}
+
+#[test]
+fn base_mode_display_falls_back_to_hide_in_base_mode() {
+    let display = |pairs: &[(&str, &str)]| base_mode_display(&configuration(pairs));
+    assert_eq!(display(&[]), BaseModeDisplay::Full);
+    assert_eq!(
+        display(&[("hide_in_base_mode", "true")]),
+        BaseModeDisplay::Hidden
+    );
+    assert_eq!(
+        display(&[
+            ("hide_in_base_mode", "true"),
+            ("base_mode_display", "badge")
+        ]),
+        BaseModeDisplay::Badge
+    );
+}
@@ -0,0 +1,103 @@
+//! Keymap fixtures loaded from zellij keybinding configs in `fixtures/` by zellij's own config
+//! parser. Each fixture sets `clear-defaults=true`, so it holds exactly the bindings it lists.
+
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+use zellij_utils::input::config::Config;
+
+type Keymap = Vec<(KeyWithModifier, Vec<Action>)>;
+
+pub use crate::mode::ALL_MODES;
+
+pub struct Fixture {
+    pub name: &'static str,
+    pub base_mode: InputMode,
+    pub keybinds: Vec<(InputMode, Keymap)>,
+}
+
+impl Fixture {
+    /// Loads a fixture from a zellij config, panicking if zellij can't read it.
+    pub fn from_kdl(name: &'static str, src: &str) -> Fixture {
+        let config =
+            Config::from_kdl(src, None).unwrap_or_else(|e| panic!("fixture {}: {}", name, e));
+        // zellij keeps the bindings in hash maps; put them in a fixed order so the snapshots
+        // don't depend on it
+        let mut keybinds = config.keybinds.to_keybinds_vec();
+        keybinds.sort_by_key(|(mode, _)| ALL_MODES.iter().position(|other| other == mode));
+        for (_, keymap) in &mut keybinds {
+            keymap.sort_by(|(key, _), (other, _)| key.cmp(other));
+        }
+        Fixture {
+            name,
+            base_mode: config.options.default_mode.unwrap_or(InputMode::Normal),
+            keybinds,
+        }
+    }
+
+    pub fn mode_info(&self, mode: InputMode) -> ModeInfo {
+        ModeInfo {
+            mode,
+            base_mode: Some(self.base_mode),
+            keybinds: self.keybinds.clone(),
+            style: Style {
+                colors: styling(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// zellij's classic default preset: Ctrl-based mode switches from Normal.
+pub fn default_preset() -> Fixture {
+    Fixture::from_kdl("default", include_str!("fixtures/default.kdl"))
+}
+
+/// zellij's "unlock-first" preset: the base mode is Locked, Ctrl g unlocks into Normal where
+/// single letters pick a mode, and finishing an action drops back to Locked.
+pub fn unlock_first_preset() -> Fixture {
+    Fixture::from_kdl("unlock_first", include_str!("fixtures/unlock_first.kdl"))
+}
+
+/// A custom Colemak config: Alt-based mode switches, focus on n/e/i/o, no quit binding, and
+/// no Enter binding to leave modal modes.
+pub fn colemak_custom() -> Fixture {
+    Fixture::from_kdl(
+        "colemak_custom",
+        include_str!("fixtures/colemak_custom.kdl"),
+    )
+}
+
+/// A tmux-style config: everything hangs off a Ctrl b prefix in tmux mode, and the other modes
+/// are only reachable from there.
+pub fn tmux_style() -> Fixture {
+    Fixture::from_kdl("tmux_style", include_str!("fixtures/tmux_style.kdl"))
+}
+
+pub fn all() -> Vec<Fixture> {
+    vec![
+        default_preset(),
+        unlock_first_preset(),
+        colemak_custom(),
+        tmux_style(),
+    ]
+}
+
+/// A fixed palette so snapshots don't depend on zellij's default theme.
+pub fn styling() -> Styling {
+    let declaration = |base: u8, background: u8| StyleDeclaration {
+        base: PaletteColor::EightBit(base),
+        background: PaletteColor::EightBit(background),
+        emphasis_0: PaletteColor::EightBit(1),
+        emphasis_1: PaletteColor::EightBit(2),
+        emphasis_2: PaletteColor::EightBit(3),
+        emphasis_3: PaletteColor::EightBit(4),
+    };
+    Styling {
+        text_unselected: declaration(7, 0),
+        text_selected: declaration(15, 8),
+        ribbon_unselected: declaration(0, 4),
+        ribbon_selected: declaration(0, 2),
+        ..Default::default()
+    }
+}
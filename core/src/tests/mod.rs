@@ -0,0 +1,1940 @@
+//! Golden-file snapshots of the rendered hints for every mode of a few representative keymaps,
+//! loaded from the zellij configs in `fixtures/`.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1 cargo test -p zjstatus-hints-core` to rewrite the snapshots in
+//! `core/src/tests/snapshots/` after an intended change to the output, then review the diff like
+//! any other change.
+
+mod ansi;
+mod config;
+mod fixtures;
+mod schema;
+
+use crate::actions::parse_action_patterns;
+use crate::ansi::{calculate_visible_length, strip_ansi};
+use crate::keys::plugin_keys;
+use crate::style::{Colour, Style, StyledString};
+use crate::{
+    base_mode_parts, collect_hints_for_mode, fit_hints, format_line, get_keymap_for_mode,
+    join_hints, keymap_hints, one_line_ui, parse_layout, render_hint_page, render_hints_for_mode,
+    ActionMatch, Align, BaseModeDisplay, ContextHints, CustomHint, HighlightMode, HintContext,
+    HintStyle, IconTheme, KeyCaps, KeyNames, KeyOptions, KeyOrder, KeysPerAction, Labels,
+    LineOptions, ModifierOrder, Modifiers, OverflowPosition, Preset, Profile, RenderOptions,
+    SuperLabel, TextAttributes, Theme,
+};
+use fixtures::{Fixture, ALL_MODES};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+
+fn render(fixture: &Fixture, mode: InputMode) -> String {
+    render_with(fixture, mode, &RenderOptions::default())
+}
+
+fn render_with(fixture: &Fixture, mode: InputMode, options: &RenderOptions) -> String {
+    let mode_info = fixture.mode_info(mode);
+    let parts = render_hints_for_mode(
+        mode,
+        &get_keymap_for_mode(&mode_info),
+        &mode_info.style.colors,
+        options,
+        &HintContext::new(fixture.base_mode, &[], &PaneManifest::default()),
+    );
+    format_line(&parts, &LineOptions::default())
+}
+
+/// One section per mode: the visible text, then the raw output with escapes spelled out so
+/// colour changes show up in diffs too.
+fn snapshot(fixture: &Fixture) -> String {
+    let mut out = String::new();
+    for mode in ALL_MODES {
+        let output = render(fixture, mode);
+        out.push_str(&format!("== {:?} ==\n", mode));
+        out.push_str(&format!("{}\n", strip_ansi(&output)));
+        out.push_str(&format!("{}\n\n", output.replace('\x1b', "\\e")));
+    }
+    out
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/snapshots")
+        .join(format!("{}.snap", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    if expected != actual {
+        panic!(
+            "snapshot {} does not match the rendered output; rerun with UPDATE_SNAPSHOTS=1 \
+             and review the diff if the change is intended\n--- expected\n{}\n--- actual\n{}",
+            path.display(),
+            expected,
+            actual
+        );
+    }
+}
+
+#[test]
+fn default_preset_snapshot() {
+    let fixture = fixtures::default_preset();
+    assert_snapshot(fixture.name, &snapshot(&fixture));
+}
+
+#[test]
+fn unlock_first_preset_snapshot() {
+    let fixture = fixtures::unlock_first_preset();
+    assert_snapshot(fixture.name, &snapshot(&fixture));
+}
+
+#[test]
+fn colemak_custom_snapshot() {
+    let fixture = fixtures::colemak_custom();
+    assert_snapshot(fixture.name, &snapshot(&fixture));
+}
+
+#[test]
+fn tmux_style_snapshot() {
+    let fixture = fixtures::tmux_style();
+    assert_snapshot(fixture.name, &snapshot(&fixture));
+}
+
+#[test]
+fn every_fixture_renders_something_outside_the_base_mode() {
+    for fixture in fixtures::all() {
+        for mode in [InputMode::Pane, InputMode::Tab, InputMode::Scroll] {
+            let output = render(&fixture, mode);
+            assert!(
+                !strip_ansi(&output).trim().is_empty(),
+                "{} rendered nothing in {:?}",
+                fixture.name,
+                mode
+            );
+        }
+    }
+}
+
+/// The hints of `fixture`'s base mode, drawn as `display` asks.
+fn render_base_mode(
+    fixture: &Fixture,
+    display: BaseModeDisplay,
+    sync_indicator: Option<&str>,
+) -> Vec<StyledString> {
+    let mode_info = fixture.mode_info(fixture.base_mode);
+    let hints = || {
+        render_hints_for_mode(
+            fixture.base_mode,
+            &get_keymap_for_mode(&mode_info),
+            &mode_info.style.colors,
+            &RenderOptions::default(),
+            &HintContext::new(fixture.base_mode, &[], &PaneManifest::default()),
+        )
+    };
+    base_mode_parts(
+        display,
+        fixture.base_mode,
+        hints,
+        sync_indicator,
+        &mode_info.style.colors,
+    )
+}
+
+#[test]
+fn dimmed_base_mode_keeps_every_hint() {
+    let fixture = fixtures::default_preset();
+    let full = render_base_mode(&fixture, BaseModeDisplay::Full, None);
+    let dimmed = render_base_mode(&fixture, BaseModeDisplay::Dimmed, None);
+    let line = |parts: &[StyledString]| format_line(parts, &LineOptions::default());
+    assert_eq!(strip_ansi(&line(&dimmed)), strip_ansi(&line(&full)));
+    let is_dimmed = |part: &StyledString| {
+        let part = part.to_string();
+        let codes = part
+            .strip_prefix("\x1b[")
+            .and_then(|rest| rest.split_once('m'));
+        codes.is_some_and(|(codes, _)| codes.split(';').any(|code| code == "2"))
+    };
+    assert!(!full.iter().any(is_dimmed));
+    assert!(dimmed.iter().all(is_dimmed));
+}
+
+#[test]
+fn badge_base_mode_shows_only_the_mode() {
+    let fixture = fixtures::unlock_first_preset();
+    let line = |parts: &[StyledString]| strip_ansi(&format_line(parts, &LineOptions::default()));
+    let badge = render_base_mode(&fixture, BaseModeDisplay::Badge, None);
+    assert_eq!(line(&badge), "  LOCKED ");
+    // In the selected ribbon's colors, as zellij's status bar draws the mode
+    assert!(
+        badge[0].to_string().starts_with("\x1b[1;38;5;0;48;5;2m"),
+        "{:?}",
+        badge
+    );
+    // The sync warning still leads it
+    let synced = render_base_mode(&fixture, BaseModeDisplay::Badge, Some("SYNC"));
+    assert_eq!(line(&synced), "  SYNC  LOCKED ");
+}
+
+#[test]
+fn padding_is_kept_when_truncating() {
+    let parts = [Style::new().paint("abcdefgh")];
+    let line = |padding_left, padding_right, max_length| {
+        format_line(
+            &parts,
+            &LineOptions {
+                max_length,
+                overflow_str: "~".to_string(),
+                padding_left,
+                padding_right,
+                ..Default::default()
+            },
+        )
+    };
+    assert_eq!(line(0, 0, 0), "abcdefgh");
+    assert_eq!(line(2, 1, 0), "  abcdefgh ");
+    assert_eq!(line(2, 1, 8), "  abcd~ ");
+}
+
+#[test]
+fn overflow_str_is_styled_and_placed() {
+    let parts = [Style::new().fg(Colour::Fixed(4)).paint("abcdefgh")];
+    let line = |overflow_position| {
+        format_line(
+            &parts,
+            &LineOptions {
+                max_length: 5,
+                overflow_str: "…".to_string(),
+                overflow_style: Style::new().fg(Colour::parse("red").unwrap()).bold(),
+                overflow_position,
+                padding_left: 0,
+                ..Default::default()
+            },
+        )
+    };
+    assert_eq!(
+        line(OverflowPosition::After),
+        "\x1b[38;5;4mabcd\x1b[0m\x1b[1;38;5;1m…\x1b[0m"
+    );
+    assert_eq!(
+        line(OverflowPosition::Before),
+        "\x1b[1;38;5;1m…\x1b[0m\x1b[38;5;4mabcd\x1b[0m"
+    );
+}
+
+#[test]
+fn hints_that_overflow_are_split_into_pages() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::Pane);
+    let line = LineOptions {
+        max_length: 40,
+        paging: true,
+        ..Default::default()
+    };
+    let page = |page| {
+        let mode_hints = collect_hints_for_mode(
+            InputMode::Pane,
+            &get_keymap_for_mode(&mode_info),
+            &mode_info.style.colors,
+            &RenderOptions::default(),
+            &HintContext::new(fixture.base_mode, &[], &PaneManifest::default()),
+        );
+        let parts = render_hint_page(
+            mode_hints,
+            page,
+            &mode_info.style.colors,
+            &RenderOptions::default(),
+            &line,
+        );
+        strip_ansi(&format_line(&parts, &line))
+    };
+    assert_eq!(page(0), "   n|Alt n  new   x  x   f  full  1/5");
+    assert_eq!(page(1), "   w|Alt f  float   r|d  split →↓  2/5");
+    assert_eq!(page(2), "   c  rename  3/5");
+    // A hint too wide for any page is still cut off
+    assert!(page(3).ends_with("..."), "{:?}", page(3));
+    // Pages wrap around either way
+    assert_eq!(page(5), page(0));
+    assert_eq!(page(-1), "   z  frames   ENTER  select  5/5");
+}
+
+#[test]
+fn truncation_leaves_out_whole_hints() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::Pane);
+    let fit = |line: &LineOptions| {
+        let mode_hints = collect_hints_for_mode(
+            InputMode::Pane,
+            &get_keymap_for_mode(&mode_info),
+            &mode_info.style.colors,
+            &RenderOptions::default(),
+            &HintContext::new(fixture.base_mode, &[], &PaneManifest::default()),
+        );
+        let parts = fit_hints(
+            mode_hints,
+            &mode_info.style.colors,
+            &RenderOptions::default(),
+            line,
+        );
+        strip_ansi(&format_line(&parts, line))
+    };
+    let line = LineOptions {
+        max_length: 40,
+        ..Default::default()
+    };
+    // The way out of the mode is kept over the hints before it
+    assert_eq!(fit(&line), "   n|Alt n  new   ENTER  select ...");
+    let line = LineOptions {
+        keep_end: true,
+        overflow_position: OverflowPosition::Before,
+        ..line
+    };
+    // Toggling frames makes way before the hints at the start
+    assert_eq!(fit(&line), " ...  ENTER  select ");
+    // Nothing to gain from the cut when no hint fits on its own
+    let line = LineOptions {
+        max_length: 8,
+        ..Default::default()
+    };
+    assert_eq!(fit(&line), "   n|...");
+}
+
+#[test]
+fn mode_prefix_and_suffix_survive_truncation() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::Tab);
+    let options = RenderOptions {
+        prefixes: HashMap::from([(InputMode::Tab, ("TAB".to_string(), HintStyle::default()))]),
+        suffixes: HashMap::from([(
+            InputMode::Tab,
+            (" ⚠ sync".to_string(), HintStyle::default()),
+        )]),
+        ..Default::default()
+    };
+    let mode_hints = collect_hints_for_mode(
+        InputMode::Tab,
+        &get_keymap_for_mode(&mode_info),
+        &mode_info.style.colors,
+        &options,
+        &HintContext::new(fixture.base_mode, &[], &PaneManifest::default()),
+    );
+    let line = LineOptions {
+        max_length: 40,
+        ..Default::default()
+    };
+    let parts = fit_hints(mode_hints, &mode_info.style.colors, &options, &line);
+    assert_eq!(
+        strip_ansi(&format_line(&parts, &line)),
+        " TAB  n  new   ENTER  select ... ⚠ sync"
+    );
+}
+
+#[test]
+fn low_priority_hints_make_way_first() {
+    let fixture = fixtures::default_preset();
+    let fit = |mode: InputMode, max_length: usize| {
+        let mode_info = fixture.mode_info(mode);
+        let mode_hints = collect_hints_for_mode(
+            mode,
+            &get_keymap_for_mode(&mode_info),
+            &mode_info.style.colors,
+            &RenderOptions::default(),
+            &HintContext::new(fixture.base_mode, &[], &PaneManifest::default()),
+        );
+        let line = LineOptions {
+            max_length,
+            ..Default::default()
+        };
+        let parts = fit_hints(
+            mode_hints,
+            &mode_info.style.colors,
+            &RenderOptions::default(),
+            &line,
+        );
+        strip_ansi(&format_line(&parts, &line))
+    };
+    // Breaking panes out goes before the rest of Tab mode
+    assert_eq!(
+        fit(InputMode::Tab, 75),
+        "   n  new   x  close   s  sync   r  rename   ←→  move   ENTER  select ..."
+    );
+    // As do the toggles every mode looks for, wherever they're listed
+    assert_eq!(
+        fit(InputMode::Pane, 146),
+        "   n|Alt n  new   x  x   f  full   w|Alt f  float   r|d  split →↓   c  rename   \
+         h|j|Alt j|k|Alt k|l|←|↓|Alt ↓|↑|Alt ↑|→  move   ENTER  select ..."
+    );
+}
+
+#[test]
+fn reversed_hints_are_cut_at_the_start() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        reverse: true,
+        max_hints: 3,
+        ..Default::default()
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert_eq!(pane, "   f  full   x  x   n|Alt n  new ");
+
+    let parts = [Style::new().fg(Colour::Fixed(4)).paint("abcdefgh")];
+    let line = |overflow_position| {
+        format_line(
+            &parts,
+            &LineOptions {
+                max_length: 5,
+                overflow_str: "…".to_string(),
+                overflow_position,
+                keep_end: true,
+                padding_left: 0,
+                ..Default::default()
+            },
+        )
+    };
+    assert_eq!(line(OverflowPosition::Before), "…\x1b[38;5;4mefgh\x1b[0m");
+    assert_eq!(line(OverflowPosition::After), "\x1b[38;5;4mefgh\x1b[0m…");
+}
+
+#[test]
+fn overflow_str_can_differ_per_mode() {
+    let line = LineOptions {
+        max_length: 4,
+        padding_left: 0,
+        mode_overflow_str: HashMap::from([(InputMode::Pane, String::new())]),
+        ..Default::default()
+    };
+    let parts = [Style::new().paint("abcdefgh")];
+    assert_eq!(format_line(&parts, &line.for_mode(InputMode::Pane)), "abcd");
+    assert_eq!(format_line(&parts, &line.for_mode(InputMode::Tab)), "a...");
+}
+
+#[test]
+fn plugin_keys_lists_every_key_launching_the_plugin() {
+    let fixture = Fixture::from_kdl(
+        "plugin_keys",
+        r#"
+        keybinds clear-defaults=true {
+            session {
+                bind "w" "F1" {
+                    LaunchOrFocusPlugin "session-manager" { floating true; };
+                    SwitchToMode "Normal"
+                }
+                bind "Alt w" { LaunchPlugin "session-manager"; }
+                bind "c" { LaunchOrFocusPlugin "configuration"; SwitchToMode "Normal"; }
+            }
+        }
+        "#,
+    );
+    let keymap = get_keymap_for_mode(&fixture.mode_info(InputMode::Session));
+    let mut keys = plugin_keys(&keymap, "session-manager", &KeyOptions::default());
+    keys.sort();
+    let mut expected = vec![
+        KeyWithModifier::new(BareKey::Char('w')),
+        KeyWithModifier::new(BareKey::F(1)),
+        KeyWithModifier::new(BareKey::Char('w')).with_alt_modifier(),
+    ];
+    expected.sort();
+    assert_eq!(keys, expected);
+}
+
+#[test]
+fn session_mode_shows_kill_and_rename_keys() {
+    let fixture = Fixture::from_kdl(
+        "session_keys",
+        r#"
+        keybinds clear-defaults=true {
+            session {
+                bind "Esc" { SwitchToMode "Normal"; }
+                bind "d" { Detach; }
+                bind "q" { Quit; }
+                bind "r" { RenameSession "work"; SwitchToMode "Normal"; }
+            }
+            shared {
+                bind "Ctrl q" { Quit; }
+            }
+        }
+        "#,
+    );
+    let session = strip_ansi(&render_with(
+        &fixture,
+        InputMode::Session,
+        &RenderOptions::default(),
+    ));
+    assert_eq!(session, "   d  detach   q  kill   r  rename   ESC  select ");
+}
+
+#[test]
+fn quick_keys_follow_the_mode_switches() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        quick_keys: true,
+        ..Default::default()
+    };
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert!(
+        normal.ends_with(
+            "  quit   alt-n  new   alt-h|j|k|l|←|↓|↑|→  focus   alt-+|=|-  resize   \
+             alt-[]  layout   alt-f  float "
+        ),
+        "{:?}",
+        normal
+    );
+}
+
+#[test]
+fn keys_per_action_limits_the_keys_shown() {
+    let fixture = fixtures::default_preset();
+    let render = |per_action| {
+        let options = RenderOptions {
+            keys: KeyOptions {
+                per_action,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        strip_ansi(&render_with(&fixture, InputMode::Pane, &options))
+    };
+    assert!(render(KeysPerAction::All).starts_with("   n|Alt n  new "));
+    assert!(render(KeysPerAction::Limit(1)).starts_with("   n  new "));
+    // A limit above the number of keys keeps them all, and one of 0 still shows a key
+    assert_eq!(render(KeysPerAction::Limit(5)), render(KeysPerAction::All));
+    assert_eq!(
+        render(KeysPerAction::Limit(0)),
+        render(KeysPerAction::Limit(1))
+    );
+
+    assert_eq!(KeysPerAction::parse("first"), Some(KeysPerAction::Limit(1)));
+    assert_eq!(KeysPerAction::parse(" All "), Some(KeysPerAction::All));
+    assert_eq!(KeysPerAction::parse("3"), Some(KeysPerAction::Limit(3)));
+    assert_eq!(KeysPerAction::parse("some"), None);
+}
+
+#[test]
+fn key_order_picks_the_keys_listed_first() {
+    let keymap = [
+        (BareKey::Left, Direction::Left),
+        (BareKey::Char('h'), Direction::Left),
+        (BareKey::Down, Direction::Down),
+        (BareKey::Char('j'), Direction::Down),
+    ]
+    .map(|(key, direction)| {
+        (
+            KeyWithModifier::new(key),
+            vec![Action::MoveFocus(direction)],
+        )
+    });
+    let render = |order| {
+        let options = RenderOptions {
+            keys: KeyOptions {
+                order,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let context = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+        let parts = render_hints_for_mode(
+            InputMode::Pane,
+            &keymap,
+            &Styling::default(),
+            &options,
+            &context,
+        );
+        strip_ansi(&format_line(&parts, &LineOptions::default()))
+    };
+    assert_eq!(render(KeyOrder::Letters), "   h|j|←|↓  move ");
+    assert_eq!(render(KeyOrder::Arrows), "   ←|↓|h|j  move ");
+    // zellij's own order, arrow first for each direction
+    assert_eq!(render(KeyOrder::Keymap), "   ←|h|↓|j  move ");
+
+    assert_eq!(KeyOrder::parse("Arrows"), Some(KeyOrder::Arrows));
+    assert_eq!(KeyOrder::parse("alphabetical"), None);
+}
+
+#[test]
+fn unbound_hints_are_shown_as_missing_on_request() {
+    let keymap = [(
+        KeyWithModifier::new(BareKey::Char('n')),
+        vec![
+            Action::NewTab(None, vec![], None, None, None, true),
+            Action::SwitchToMode(InputMode::Normal),
+        ],
+    )];
+    let render = |show_missing| {
+        let options = RenderOptions {
+            show_missing,
+            ..Default::default()
+        };
+        let context = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+        let parts = render_hints_for_mode(
+            InputMode::Tab,
+            &keymap,
+            &fixtures::styling(),
+            &options,
+            &context,
+        );
+        format_line(&parts, &LineOptions::default())
+    };
+    assert_eq!(strip_ansi(&render(false)), "   n  new ");
+    let shown = render(true);
+    // Every built-in hint of the mode is listed, bound or not
+    let text = strip_ansi(&shown);
+    assert!(
+        text.starts_with("   n  new   ✗ close   ✗ break pane "),
+        "{:?}",
+        text
+    );
+    assert!(
+        shown.contains("\x1b[2;38;5;7;48;5;0m ✗ close "),
+        "{:?}",
+        shown
+    );
+}
+
+#[test]
+fn keys_can_be_filtered_by_modifier() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        keys: KeyOptions {
+            only_modifiers: Modifiers::parse("Alt"),
+            ..Default::default()
+        },
+        quick_keys: true,
+        ..Default::default()
+    };
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert!(normal.starts_with("   alt-n  new "), "{:?}", normal);
+
+    let options = RenderOptions {
+        keys: KeyOptions {
+            exclude_modifiers: Modifiers::parse("ctrl").unwrap(),
+            ..Default::default()
+        },
+        quick_keys: true,
+        ..Default::default()
+    };
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert!(!normal.contains("ctrl"), "{:?}", normal);
+    assert!(normal.contains("alt-n  new "), "{:?}", normal);
+
+    assert_eq!(Modifiers::parse("meta"), None);
+}
+
+#[test]
+fn modifier_filters_leave_the_other_modes_alone() {
+    let options = RenderOptions {
+        keys: KeyOptions {
+            only_modifiers: Modifiers::parse("Alt"),
+            exclude_modifiers: Modifiers::parse("ctrl").unwrap(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    // Pane mode's plain letters and the Ctrl keys back out of Locked mode all stay
+    for fixture in [fixtures::default_preset(), fixtures::unlock_first_preset()] {
+        for mode in [InputMode::Pane, InputMode::Locked] {
+            assert_eq!(
+                render_with(&fixture, mode, &options),
+                render_with(&fixture, mode, &RenderOptions::default()),
+                "{:?} in {}",
+                mode,
+                fixture.name
+            );
+        }
+    }
+    let pane = strip_ansi(&render_with(
+        &fixtures::default_preset(),
+        InputMode::Pane,
+        &options,
+    ));
+    assert!(pane.starts_with("   n|Alt n  new   x  x "), "{:?}", pane);
+}
+
+#[test]
+fn modifiers_follow_the_configured_order() {
+    let fixture = Fixture::from_kdl(
+        "modifier_order",
+        r#"
+        keybinds clear-defaults=true {
+            pane {
+                bind "Ctrl Alt n" { NewPane; SwitchToMode "Normal"; }
+            }
+        }
+        "#,
+    );
+    let pane = strip_ansi(&render(&fixture, InputMode::Pane));
+    assert!(pane.starts_with("   ctrl-alt-n  new "), "{:?}", pane);
+
+    let options = RenderOptions {
+        keys: KeyOptions {
+            modifier_order: ModifierOrder::parse("alt").unwrap(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert!(pane.starts_with("   alt-ctrl-n  new "), "{:?}", pane);
+
+    assert_eq!(ModifierOrder::parse("alt, meta"), None);
+}
+
+#[test]
+fn super_is_written_as_configured() {
+    let fixture = Fixture::from_kdl(
+        "super_label",
+        r#"
+        keybinds clear-defaults=true {
+            pane {
+                bind "Super n" { NewPane; SwitchToMode "Normal"; }
+            }
+        }
+        "#,
+    );
+    let pane = strip_ansi(&render(&fixture, InputMode::Pane));
+    assert!(pane.starts_with("   super-n  new "), "{:?}", pane);
+
+    for (label, expected) in [("cmd", "   cmd-n  new "), ("❖", "   ❖-n  new ")] {
+        let options = RenderOptions {
+            keys: KeyOptions {
+                super_label: SuperLabel::parse(label).unwrap(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+        assert!(pane.starts_with(expected), "{:?}", pane);
+    }
+}
+
+#[test]
+fn special_keys_have_readable_names() {
+    let fixture = Fixture::from_kdl(
+        "key_names",
+        r#"
+        keybinds clear-defaults=true {
+            scroll {
+                bind "Space" "PageDown" { PageScrollDown; }
+                bind "Backspace" "PageUp" { PageScrollUp; }
+            }
+        }
+        "#,
+    );
+    let scroll = strip_ansi(&render(&fixture, InputMode::Scroll));
+    assert!(scroll.contains("Space|PgDn"), "{:?}", scroll);
+    assert!(scroll.contains("BS|PgUp"), "{:?}", scroll);
+
+    let options = RenderOptions {
+        keys: KeyOptions {
+            key_names: KeyNames::parse("space=␣, backspace = ⌫").unwrap(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let scroll = strip_ansi(&render_with(&fixture, InputMode::Scroll, &options));
+    assert!(scroll.contains("␣|PgDn"), "{:?}", scroll);
+    assert!(scroll.contains("⌫|PgUp"), "{:?}", scroll);
+
+    assert!(KeyNames::parse("Ctrl space=␣").is_err());
+}
+
+#[test]
+fn merged_pages_show_the_keys_of_both() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        merge_pages: true,
+        ..Default::default()
+    };
+    for mode in [InputMode::Scroll, InputMode::Search] {
+        let line = strip_ansi(&render_with(&fixture, mode, &options));
+        assert!(
+            line.contains("   l|Ctrl f|h|Ctrl b|d|u|→|←|PgDn|PgUp  page/½page   "),
+            "{:?}",
+            line
+        );
+        assert!(!line.contains("half page"), "{:?}", line);
+    }
+}
+
+#[test]
+fn theme_overrides_the_colors_it_sets() {
+    let theme = Theme::parse("key_bg=#1e1e2e, label_fg = 15").unwrap();
+    let colors = theme.apply(&Styling::default());
+    assert_eq!(
+        colors.ribbon_unselected.background,
+        PaletteColor::Rgb((0x1e, 0x1e, 0x2e))
+    );
+    assert_eq!(colors.text_unselected.base, PaletteColor::EightBit(15));
+    assert_eq!(
+        colors.text_unselected.background,
+        Styling::default().text_unselected.background
+    );
+
+    assert!(Theme::parse("key_bg=nope").is_err());
+    assert!(Theme::parse("border=red").is_err());
+}
+
+#[test]
+fn separator_goes_between_hints_only() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        separator: " | ".to_string(),
+        ..Default::default()
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert!(
+        pane.starts_with("   n|Alt n  new  |  x  x  | "),
+        "{:?}",
+        pane
+    );
+    assert!(!pane.trim_end().ends_with('|'), "{:?}", pane);
+
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert!(
+        normal.starts_with("   ctrl  pane  |  tab  | "),
+        "{:?}",
+        normal
+    );
+}
+
+#[test]
+fn max_hints_limits_each_mode() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        max_hints: 2,
+        mode_max_hints: [(InputMode::Normal, 3)].into_iter().collect(),
+        ..Default::default()
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert_eq!(pane.trim_end(), "   n|Alt n  new   x  x");
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert_eq!(normal.trim_end(), "   ctrl  pane  tab  n-resize");
+}
+
+#[test]
+fn hidden_hints_are_left_out() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        hidden_hints: [
+            (InputMode::Normal, vec!["quit".to_string()]),
+            (
+                InputMode::Pane,
+                vec!["split".to_string(), "close".to_string()],
+            ),
+        ]
+        .into_iter()
+        .collect(),
+        max_hints: 3,
+        ..Default::default()
+    };
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert!(!normal.contains("quit"), "{:?}", normal);
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert_eq!(
+        pane.trim_end(),
+        "   n|Alt n  new   f  full   w|Alt f  float"
+    );
+    let pane = strip_ansi(&render_with(
+        &fixture,
+        InputMode::Pane,
+        &RenderOptions {
+            max_hints: 0,
+            ..options
+        },
+    ));
+    assert!(
+        pane.starts_with("   n|Alt n  new   f  full   w|Alt f  float   c  rename "),
+        "{:?}",
+        pane
+    );
+}
+
+#[test]
+fn hint_order_picks_and_orders_hints() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        hint_order: HashMap::from([
+            (
+                InputMode::Normal,
+                vec!["quit".to_string(), "tmux".to_string(), "pane".to_string()],
+            ),
+            (
+                InputMode::Pane,
+                vec!["split".to_string(), "new".to_string()],
+            ),
+        ]),
+        ..Default::default()
+    };
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert_eq!(normal, "   ctrl  quit  tmux  pane ");
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert_eq!(pane, "   r|d  split →↓   n|Alt n  new ");
+}
+
+#[test]
+fn custom_hints_match_exactly_or_by_prefix() {
+    let fixture = fixtures::default_preset();
+    let custom = |matching| RenderOptions {
+        custom_hints: HashMap::from([(
+            InputMode::Pane,
+            vec![CustomHint {
+                label: "embed".to_string(),
+                actions: parse_action_patterns("TogglePaneEmbedOrFloating").unwrap(),
+                matching,
+                style: HintStyle::default(),
+            }],
+        )]),
+        ..Default::default()
+    };
+    // "e" also switches back to Normal mode, so only a prefix match finds it
+    let exact = strip_ansi(&render_with(
+        &fixture,
+        InputMode::Pane,
+        &custom(ActionMatch::Exact),
+    ));
+    assert!(!exact.contains("embed"), "{:?}", exact);
+    let prefix = strip_ansi(&render_with(
+        &fixture,
+        InputMode::Pane,
+        &custom(ActionMatch::Prefix),
+    ));
+    assert!(
+        prefix.ends_with("  z  frames   e  embed   ENTER  select "),
+        "{:?}",
+        prefix
+    );
+}
+
+#[test]
+fn wildcards_match_any_arguments() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        custom_hints: HashMap::from([(
+            InputMode::Pane,
+            vec![CustomHint {
+                label: "any pane".to_string(),
+                actions: parse_action_patterns("NewPane \"*\"; SwitchToMode \"Normal\"").unwrap(),
+                matching: ActionMatch::Exact,
+                style: HintStyle::default(),
+            }],
+        )]),
+        ..Default::default()
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert!(
+        pane.ends_with("  d|n|r  any pane   ENTER  select "),
+        "{:?}",
+        pane
+    );
+}
+
+#[test]
+fn layouts_declare_the_hint_line() {
+    let fixture = fixtures::default_preset();
+    let layout = parse_layout(
+        "mode-badge\n\
+         hint \"new\" label=\"open\"\n\
+         text \" |\"\n\
+         key-hint label=\"frames\" match=\"prefix\" { TogglePaneFrames; }\n\
+         hint \"select\"",
+    )
+    .unwrap();
+    let options = RenderOptions {
+        layouts: HashMap::from([(InputMode::Pane, layout)]),
+        ..Default::default()
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert_eq!(
+        pane,
+        "   PANE   n|Alt n  open  | z  frames   ENTER  select "
+    );
+
+    assert!(parse_layout("hint").is_err());
+    assert!(parse_layout("key-hint label=\"x\"").is_err());
+    assert!(parse_layout("banner \"hi\"").is_err());
+}
+
+#[test]
+fn hints_take_their_own_colors() {
+    let fixture = fixtures::default_preset();
+    let layout =
+        parse_layout("hint \"new\" fg=\"red\" bg=\"#000000\" bold=true\nhint \"close\"").unwrap();
+    let options = RenderOptions {
+        layouts: HashMap::from([(InputMode::Pane, layout)]),
+        ..Default::default()
+    };
+    let pane = render_with(&fixture, InputMode::Pane, &options);
+    let (new, close) = pane.split_once(" new ").unwrap();
+    assert!(new.contains("\x1b[1;38;5;1;48;2;0;0;0m"), "{:?}", new);
+    assert!(!close.contains("38;5;1m"), "{:?}", close);
+
+    assert!(parse_layout("hint \"new\" fg=\"rouge\"").is_err());
+}
+
+#[test]
+fn layout_text_is_styled_and_not_counted_as_a_hint() {
+    let fixture = fixtures::default_preset();
+    let layout = parse_layout(
+        "text \"[\" fg=\"blue\"\n\
+         hint \"new\"\n\
+         spacer 2\n\
+         hint \"close\"\n\
+         text \" •\"\n\
+         hint \"select\"",
+    )
+    .unwrap();
+    let options = RenderOptions {
+        layouts: HashMap::from([(InputMode::Pane, layout)]),
+        ..Default::default()
+    };
+    let pane = render_with(&fixture, InputMode::Pane, &options);
+    assert!(pane.contains("\x1b[38;5;4m[\x1b[0m"), "{:?}", pane);
+    assert_eq!(
+        strip_ansi(&pane),
+        " [ n|Alt n  new    x  x  • ENTER  select "
+    );
+
+    // The text before the hints left out goes with them
+    let options = RenderOptions {
+        max_hints: 2,
+        ..options
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert_eq!(pane, " [ n|Alt n  new    x  x ");
+
+    assert!(parse_layout("spacer \"wide\"").is_err());
+}
+
+#[test]
+fn select_hint_label_and_key_are_configurable() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        select_label: Some("back".to_string()),
+        select_key: Some(crate::parse_key("Esc").unwrap()),
+        ..Default::default()
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert!(pane.ends_with("  ESC  back "), "{:?}", pane);
+
+    // Falls back to the first key back to Normal mode when the preferred one isn't bound
+    let options = RenderOptions {
+        select_key: Some(crate::parse_key("F12").unwrap()),
+        ..Default::default()
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert!(pane.ends_with("  select "), "{:?}", pane);
+}
+
+#[test]
+fn locked_indicator_replaces_locked_hints() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        locked_indicator: Some("🔒".to_string()),
+        ..Default::default()
+    };
+    let locked = strip_ansi(&render_with(&fixture, InputMode::Locked, &options));
+    assert_eq!(locked, "   🔒 ");
+}
+
+#[test]
+fn tmux_indicator_leads_tmux_hints() {
+    let fixture = fixtures::tmux_style();
+    let options = RenderOptions {
+        tmux_indicator: Some("PREFIX".to_string()),
+        ..Default::default()
+    };
+    let tmux = strip_ansi(&render_with(&fixture, InputMode::Tmux, &options));
+    assert_eq!(tmux, "   PREFIX   ENTER|ESC  normal ");
+}
+
+#[test]
+fn relabels_replace_the_labels_of_matching_hints() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        relabels: HashMap::from([(
+            InputMode::Pane,
+            vec![("split".to_string(), "split".to_string())],
+        )]),
+        separate_splits: true,
+        ..Default::default()
+    };
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert!(pane.contains("   r  split   d  split "), "{:?}", pane);
+}
+
+#[test]
+fn minimal_labels_keep_the_mnemonic_letter() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        labels: Labels::Minimal,
+        ..Default::default()
+    };
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert_eq!(normal, "   ctrl  p  t  n  h  s  o  q ");
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert!(
+        pane.starts_with("   n|Alt n  n   x  x   f  f   w|Alt f  f "),
+        "{:?}",
+        pane
+    );
+}
+
+#[test]
+fn remapped_keys_highlight_the_first_letter_unless_strict() {
+    let fixture = fixtures::colemak_custom();
+    let tab = render_with(&fixture, InputMode::Tab, &RenderOptions::default());
+    assert!(tab.contains("\x1b[1;38;5;0;48;5;0mc\x1b[0m\x1b[38;5;7;48;5;0mlose "));
+    let options = RenderOptions {
+        strict_mnemonics: true,
+        ..Default::default()
+    };
+    let tab = render_with(&fixture, InputMode::Tab, &options);
+    assert!(tab.contains("\x1b[38;5;7;48;5;0m close "));
+}
+
+#[test]
+fn text_attributes_replace_the_built_in_ones() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        keys: KeyOptions {
+            attributes: TextAttributes {
+                bold: Some(false),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        label_attributes: TextAttributes {
+            italic: Some(true),
+            dimmed: Some(true),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let tab = render_with(&fixture, InputMode::Tab, &options);
+    assert!(tab.starts_with("  \x1b[38;5;0;48;5;4m n \x1b[0m\x1b[38;5;7;48;5;0m \x1b[0m"));
+    assert!(tab.contains("\x1b[1;2;3;38;5;0;48;5;0mn\x1b[0m\x1b[2;3;38;5;7;48;5;0mew"));
+}
+
+#[test]
+fn key_caps_wrap_each_pill() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        keys: KeyOptions {
+            caps: Some(KeyCaps {
+                background: Colour::parse("#1e1e2e"),
+                ..KeyCaps::parse("rounded").unwrap()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let tab = render_with(&fixture, InputMode::Tab, &options);
+    assert!(
+        strip_ansi(&tab).starts_with("  \u{e0b6} n \u{e0b4} new  \u{e0b6} x \u{e0b4} close "),
+        "{:?}",
+        strip_ansi(&tab)
+    );
+    assert!(tab.contains("\x1b[38;5;4;48;2;30;30;46m\u{e0b6}\x1b[0m"));
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert!(
+        normal.starts_with("  \u{e0b6} ctrl \u{e0b4} pane "),
+        "{:?}",
+        normal
+    );
+    assert_eq!(KeyCaps::parse("[]").map(|caps| caps.right), Some(']'));
+    assert_eq!(KeyCaps::parse("none"), None);
+}
+
+#[test]
+fn profiles_tailor_the_normal_mode_hints() {
+    let options = RenderOptions {
+        profile: Profile::UnlockFirst,
+        ..Default::default()
+    };
+    let fixture = fixtures::unlock_first_preset();
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert_eq!(
+        normal,
+        "   p  pane   t  tab   n  resize   h  move   s  scroll   o  session   q  quit   Ctrl g|ENTER|ESC  lock "
+    );
+    let locked = strip_ansi(&render_with(&fixture, InputMode::Locked, &options));
+    assert_eq!(locked, "   ctrl-g  unlock ");
+    let options = RenderOptions {
+        profile: Profile::Minimal,
+        ..Default::default()
+    };
+    let normal = strip_ansi(&render_with(
+        &fixtures::default_preset(),
+        InputMode::Normal,
+        &options,
+    ));
+    assert_eq!(normal, "   ctrl  pane  tab  scroll  quit ");
+}
+
+#[test]
+fn ribbons_match_zellijs_status_bar() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        ribbons: true,
+        ..Default::default()
+    };
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert_eq!(
+        normal,
+        "   ctrl \u{e0b0} <p> pane \u{e0b0}\u{e0b0} <t> tab \u{e0b0}\u{e0b0} <n> n-resize \u{e0b0}\u{e0b0} <h> h-move \u{e0b0}\u{e0b0} <s> scroll \u{e0b0}\u{e0b0} <o> o-session \u{e0b0}\u{e0b0} <q> quit \u{e0b0}"
+    );
+    let pane = strip_ansi(&render_with(&fixture, InputMode::Pane, &options));
+    assert!(pane.starts_with(" \u{e0b0} <n|Alt n> new \u{e0b0}\u{e0b0} <x> x \u{e0b0}"));
+
+    // Without arrow fonts, ribbons are set apart by spaces
+    let mode_info = fixture.mode_info(InputMode::Pane);
+    let mut context = HintContext::new(fixture.base_mode, &[], &PaneManifest::default());
+    context.simplified_ui = true;
+    let parts = render_hints_for_mode(
+        InputMode::Pane,
+        &get_keymap_for_mode(&mode_info),
+        &mode_info.style.colors,
+        &options,
+        &context,
+    );
+    let pane = strip_ansi(&format_line(&parts, &LineOptions::default()));
+    assert!(pane.is_ascii(), "{:?}", pane);
+    assert!(pane.starts_with("  <n|Alt n> new   <x> x  "), "{:?}", pane);
+}
+
+#[test]
+fn highlighted_mode_switch_takes_the_selected_colors() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::Normal);
+    let mut context = HintContext::new(fixture.base_mode, &[], &PaneManifest::default());
+    let options = RenderOptions {
+        highlight_mode: HighlightMode::Last,
+        ..Default::default()
+    };
+    let render = |options: &RenderOptions, context: &HintContext| {
+        let parts = render_hints_for_mode(
+            InputMode::Normal,
+            &get_keymap_for_mode(&mode_info),
+            &mode_info.style.colors,
+            options,
+            context,
+        );
+        format_line(&parts, &LineOptions::default())
+    };
+    let plain = render(&RenderOptions::default(), &context);
+    assert_eq!(render(&options, &context), plain);
+
+    context.last_mode = Some(InputMode::Resize);
+    let highlighted = render(&options, &context);
+    assert_ne!(highlighted, plain);
+    assert_eq!(strip_ansi(&highlighted), strip_ansi(&plain));
+    // The hints before resize's are untouched
+    let before = |line: &str| line[..line.find("ab").unwrap()].to_string();
+    assert_eq!(before(&highlighted), before(&plain));
+
+    assert_eq!(
+        HighlightMode::parse("tab"),
+        Some(HighlightMode::Favorite(InputMode::Tab))
+    );
+}
+
+#[test]
+fn one_line_ui_fits_the_hints_in_a_line_part() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::Pane);
+    let context = HintContext::new(fixture.base_mode, &[], &PaneManifest::default());
+    let options = RenderOptions::default();
+
+    let whole = one_line_ui(&mode_info, 0, &options, &context);
+    let line = render_with(&fixture, InputMode::Pane, &options);
+    assert_eq!(whole.part, line);
+    assert_eq!(whole.len, calculate_visible_length(&line));
+
+    let cut = one_line_ui(&mode_info, 30, &options, &context);
+    assert!(cut.len <= 30, "{:?}", cut);
+    assert!(strip_ansi(&cut.part).ends_with("..."), "{:?}", cut);
+}
+
+#[test]
+fn keymap_hints_list_the_raw_bindings_in_pages() {
+    let keymap = vec![
+        (
+            KeyWithModifier::new(BareKey::Char('p')).with_ctrl_modifier(),
+            vec![Action::SwitchToMode(InputMode::Pane)],
+        ),
+        (
+            KeyWithModifier::new(BareKey::Char('q')).with_ctrl_modifier(),
+            vec![Action::Quit],
+        ),
+    ];
+    let whole = strip_ansi(&format_line(
+        &join_hints(
+            keymap_hints(&keymap),
+            &Styling::default(),
+            &RenderOptions::default(),
+        ),
+        &LineOptions::default(),
+    ));
+    assert_eq!(whole, "  Ctrl p: [SwitchToMode(Pane)] Ctrl q: [Quit]");
+
+    let line = LineOptions {
+        max_length: 36,
+        ..LineOptions::default()
+    };
+    let page = |page| {
+        let parts = render_hint_page(
+            keymap_hints(&keymap),
+            page,
+            &Styling::default(),
+            &RenderOptions::default(),
+            &line,
+        );
+        strip_ansi(&format_line(&parts, &line))
+    };
+    assert_eq!(page(0), "  Ctrl p: [SwitchToMode(Pane)] 1/2");
+    assert_eq!(page(1), "  Ctrl q: [Quit] 2/2");
+}
+
+#[test]
+fn locked_bindings_precede_the_unlock_hint() {
+    let ctrl = |c| KeyWithModifier::new(BareKey::Char(c)).with_ctrl_modifier();
+    let keymap = vec![
+        (ctrl('g'), vec![Action::SwitchToMode(InputMode::Normal)]),
+        (ctrl('u'), vec![Action::HalfPageScrollUp]),
+        (ctrl('d'), vec![Action::HalfPageScrollDown]),
+        (ctrl('y'), vec![Action::HalfPageScrollUp]),
+    ];
+    let context = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+    let render = |options: &RenderOptions| {
+        let parts = render_hints_for_mode(
+            InputMode::Locked,
+            &keymap,
+            &Styling::default(),
+            options,
+            &context,
+        );
+        strip_ansi(&format_line(&parts, &LineOptions::default()))
+    };
+    assert_eq!(render(&RenderOptions::default()), "   ctrl-g  normal ");
+    let options = RenderOptions {
+        locked_show_bindings: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        render(&options),
+        "   ctrl-u|y  half page scroll up   ctrl-d  half page scroll down   ctrl-g  normal "
+    );
+}
+
+#[test]
+fn emoji_icons_precede_the_mode_switches() {
+    let fixture = fixtures::default_preset();
+    let options = RenderOptions {
+        icon_theme: IconTheme::Emoji,
+        ..Default::default()
+    };
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &options));
+    assert!(
+        normal.starts_with("   ctrl  🪟 pane  📑 tab  📐 n-resize "),
+        "{:?}",
+        normal
+    );
+    let line = LineOptions {
+        max_length: 20,
+        overflow_str: String::new(),
+        ..Default::default()
+    };
+    let parts = render_hints_for_mode(
+        InputMode::Normal,
+        &get_keymap_for_mode(&fixture.mode_info(InputMode::Normal)),
+        &fixture.mode_info(InputMode::Normal).style.colors,
+        &options,
+        &HintContext::new(fixture.base_mode, &[], &PaneManifest::default()),
+    );
+    let truncated = format_line(&parts, &line);
+    assert!(calculate_visible_length(&truncated) <= 20);
+    assert_eq!(strip_ansi(&truncated), "   ctrl  🪟 pane  📑");
+}
+
+#[test]
+fn style_presets_prepopulate_options() {
+    let fixture = fixtures::default_preset();
+    let preset = Preset::parse(" Minimal ").unwrap();
+    let normal = strip_ansi(&render_with(&fixture, InputMode::Normal, &preset.render));
+    assert_eq!(normal, "   ctrl  p  t  n  h  s  o  q ");
+    assert_eq!(preset.line.padding_left, 0);
+
+    assert_eq!(Preset::parse("default"), Some(Preset::default()));
+    assert!(!Preset::parse("plain").unwrap().colors);
+    assert_eq!(Preset::parse("fancy"), None);
+}
+
+#[test]
+fn verbose_description_spells_out_keys() {
+    let fixture = fixtures::default_preset();
+    let describe = |mode| {
+        let mode_info = fixture.mode_info(mode);
+        crate::describe_hints_for_mode(
+            mode,
+            &get_keymap_for_mode(&mode_info),
+            &RenderOptions::default(),
+            &HintContext::new(fixture.base_mode, &[], &PaneManifest::default()),
+        )
+    };
+    let normal = describe(InputMode::Normal);
+    assert!(
+        normal.starts_with("Normal mode: press Ctrl p for pane mode, Ctrl t for tab mode, "),
+        "{:?}",
+        normal
+    );
+    assert!(normal.ends_with(", Ctrl q to quit."), "{:?}", normal);
+    let tab = describe(InputMode::Tab);
+    assert!(
+        tab.contains(", Left or Right to switch tabs, "),
+        "{:?}",
+        tab
+    );
+}
+
+#[test]
+fn format_fills_in_the_active_tab() {
+    let tabs = [
+        TabInfo {
+            position: 0,
+            name: "logs".to_string(),
+            ..Default::default()
+        },
+        TabInfo {
+            position: 1,
+            name: "code".to_string(),
+            active: true,
+            is_sync_panes_active: true,
+            ..Default::default()
+        },
+    ];
+    let pane = |id, is_floating| PaneInfo {
+        id,
+        is_floating,
+        is_selectable: true,
+        ..Default::default()
+    };
+    let panes = PaneManifest {
+        panes: HashMap::from([
+            (0, vec![pane(0, false), pane(1, true)]),
+            (1, vec![pane(2, false), pane(3, true), pane(4, true)]),
+        ]),
+    };
+    let context = HintContext::new(InputMode::Normal, &tabs, &panes);
+    assert_eq!(
+        context.expand(
+            "{tab}: {sync} [{floating}] {mode} {hints}",
+            InputMode::Pane,
+            "n  new"
+        ),
+        "code: sync ON [2] pane n  new"
+    );
+
+    let context = HintContext::new(InputMode::Normal, &tabs[..1], &PaneManifest::default());
+    assert_eq!(
+        context.expand("{sync}[{floating}]{hints}", InputMode::Pane, ""),
+        "[]"
+    );
+}
+
+#[test]
+fn sync_indicator_leads_while_synced() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::Pane);
+    let options = RenderOptions {
+        sync_indicator: Some("SYNC".to_string()),
+        max_hints: 1,
+        ..Default::default()
+    };
+    let mut context = HintContext::new(fixture.base_mode, &[], &PaneManifest::default());
+    let render = |context: &HintContext| {
+        let parts = render_hints_for_mode(
+            InputMode::Pane,
+            &get_keymap_for_mode(&mode_info),
+            &mode_info.style.colors,
+            &options,
+            context,
+        );
+        strip_ansi(&format_line(&parts, &LineOptions::default()))
+    };
+    assert_eq!(render(&context), "   n|Alt n  new ");
+    context.sync_active = true;
+    assert_eq!(render(&context), "  SYNC   n|Alt n  new ");
+}
+
+#[test]
+fn floating_indicator_counts_visible_floating_panes() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::Normal);
+    let options = RenderOptions {
+        floating_indicator: true,
+        ..Default::default()
+    };
+    let mut context = HintContext::new(fixture.base_mode, &[], &PaneManifest::default());
+    context.floating_pane_count = 3;
+    let render = |context: &HintContext| {
+        let parts = render_hints_for_mode(
+            InputMode::Normal,
+            &get_keymap_for_mode(&mode_info),
+            &mode_info.style.colors,
+            &options,
+            context,
+        );
+        strip_ansi(&format_line(&parts, &LineOptions::default()))
+    };
+    assert!(
+        render(&context).starts_with("   ctrl "),
+        "{:?}",
+        render(&context)
+    );
+    context.floating_visible = true;
+    let normal = render(&context);
+    assert!(
+        normal.starts_with("   alt-f  3 floating   ctrl "),
+        "{:?}",
+        normal
+    );
+}
+
+#[test]
+fn edit_and_clear_hints_follow_their_bindings_into_any_mode() {
+    let keymap = vec![
+        (
+            KeyWithModifier::new(BareKey::Char('n')),
+            vec![
+                Action::NewPane(None, None, false),
+                Action::SwitchToMode(InputMode::Normal),
+            ],
+        ),
+        (
+            KeyWithModifier::new(BareKey::Char('e')),
+            vec![
+                Action::EditScrollback,
+                Action::SwitchToMode(InputMode::Normal),
+            ],
+        ),
+        (
+            KeyWithModifier::new(BareKey::Char('c')),
+            vec![Action::ClearScreen],
+        ),
+        (
+            KeyWithModifier::new(BareKey::Enter),
+            vec![Action::SwitchToMode(InputMode::Normal)],
+        ),
+    ];
+    let context = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+    let parts = render_hints_for_mode(
+        InputMode::Pane,
+        &keymap,
+        &Styling::default(),
+        &RenderOptions::default(),
+        &context,
+    );
+    assert_eq!(
+        strip_ansi(&format_line(&parts, &LineOptions::default())),
+        "   n  new   e  edit   c  clear   ENTER  select "
+    );
+}
+
+#[test]
+fn focus_cycling_follows_the_directional_moves() {
+    let keymap = vec![
+        (
+            KeyWithModifier::new(BareKey::Char('h')),
+            vec![Action::MoveFocus(Direction::Left)],
+        ),
+        (
+            KeyWithModifier::new(BareKey::Tab),
+            vec![Action::FocusNextPane],
+        ),
+        (
+            KeyWithModifier::new(BareKey::Tab).with_shift_modifier(),
+            vec![
+                Action::FocusPreviousPane,
+                Action::SwitchToMode(InputMode::Normal),
+            ],
+        ),
+    ];
+    let tabs = [TabInfo {
+        active: true,
+        ..Default::default()
+    }];
+    let render = |tiled_panes: u32| {
+        let pane = |id| PaneInfo {
+            id,
+            is_selectable: true,
+            ..Default::default()
+        };
+        let panes = PaneManifest {
+            panes: HashMap::from([(0, (0..tiled_panes).map(pane).collect())]),
+        };
+        let context = HintContext::new(InputMode::Normal, &tabs, &panes);
+        let options = RenderOptions {
+            context_hints: ContextHints::Hide,
+            ..Default::default()
+        };
+        let parts = render_hints_for_mode(
+            InputMode::Pane,
+            &keymap,
+            &Styling::default(),
+            &options,
+            &context,
+        );
+        strip_ansi(&format_line(&parts, &LineOptions::default()))
+    };
+    assert_eq!(render(2), "   h  move   TAB  next   shift-TAB  prev ");
+    // With a single pane there's nothing to cycle through
+    assert_eq!(render(1), " ");
+}
+
+#[test]
+fn noop_actions_follow_the_tabs_and_panes() {
+    let pane = |id, is_floating| PaneInfo {
+        id,
+        is_floating,
+        is_selectable: true,
+        ..Default::default()
+    };
+    let context = |tab_count: usize, panes: Vec<PaneInfo>, floating_visible| {
+        let tabs: Vec<TabInfo> = (0..tab_count)
+            .map(|position| TabInfo {
+                position,
+                active: position == 0,
+                are_floating_panes_visible: floating_visible,
+                ..Default::default()
+            })
+            .collect();
+        let panes = PaneManifest {
+            panes: HashMap::from([(0, panes)]),
+        };
+        HintContext::new(InputMode::Normal, &tabs, &panes)
+    };
+    let one_of_each = context(1, vec![pane(0, false)], false);
+    let two_of_each = context(2, vec![pane(0, false), pane(1, false)], false);
+    for action in [
+        Action::CloseTab,
+        Action::GoToNextTab,
+        Action::GoToPreviousTab,
+        Action::BreakPane,
+        Action::MoveFocus(Direction::Left),
+        Action::FocusNextPane,
+        Action::FocusPreviousPane,
+    ] {
+        assert!(one_of_each.is_noop(&action), "{:?}", action);
+        assert!(!two_of_each.is_noop(&action), "{:?}", action);
+    }
+    // The only tiled pane can still go fullscreen, just not once it is
+    assert!(!one_of_each.is_noop(&Action::ToggleFocusFullscreen));
+    let fullscreen = PaneInfo {
+        is_focused: true,
+        is_fullscreen: true,
+        ..pane(0, false)
+    };
+    let fullscreen = context(1, vec![fullscreen, pane(1, false)], false);
+    assert!(fullscreen.is_noop(&Action::ToggleFocusFullscreen));
+    // Without floating panes there's nothing to show or hide, however many tiled ones
+    assert!(one_of_each.is_noop(&Action::ToggleFloatingPanes));
+    assert!(two_of_each.is_noop(&Action::ToggleFloatingPanes));
+    // Floating panes can be shown or hidden, whether they're shown now or not
+    for floating_visible in [true, false] {
+        let floating = context(1, vec![pane(0, false), pane(1, true)], floating_visible);
+        assert!(!floating.is_noop(&Action::ToggleFloatingPanes));
+    }
+    // Nothing is a no-op before the first TabUpdate and PaneUpdate
+    let unknown = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+    assert!(!unknown.is_noop(&Action::CloseTab));
+    assert!(!unknown.is_noop(&Action::BreakPane));
+    assert!(!unknown.is_noop(&Action::ToggleFloatingPanes));
+}
+
+#[test]
+fn float_hint_is_hidden_without_floating_panes() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::Pane);
+    let tabs = [TabInfo {
+        active: true,
+        ..Default::default()
+    }];
+    let pane = |id, is_floating| PaneInfo {
+        id,
+        is_floating,
+        is_selectable: true,
+        ..Default::default()
+    };
+    let render = |panes: Vec<PaneInfo>| {
+        let panes = PaneManifest {
+            panes: HashMap::from([(0, panes)]),
+        };
+        let options = RenderOptions {
+            context_hints: ContextHints::Hide,
+            ..Default::default()
+        };
+        let parts = render_hints_for_mode(
+            InputMode::Pane,
+            &get_keymap_for_mode(&mode_info),
+            &mode_info.style.colors,
+            &options,
+            &HintContext::new(fixture.base_mode, &tabs, &panes),
+        );
+        strip_ansi(&format_line(&parts, &LineOptions::default()))
+    };
+    let tiled = render(vec![pane(0, false), pane(1, false)]);
+    assert!(!tiled.contains("float"), "{:?}", tiled);
+    let floating = render(vec![pane(0, false), pane(1, true)]);
+    assert!(floating.contains("w|Alt f  float"), "{:?}", floating);
+}
+
+#[test]
+fn noop_hints_are_dimmed_or_hidden() {
+    let keymap = vec![
+        (
+            KeyWithModifier::new(BareKey::Char('n')),
+            vec![
+                Action::NewTab(None, vec![], None, None, None, true),
+                Action::SwitchToMode(InputMode::Normal),
+            ],
+        ),
+        (
+            KeyWithModifier::new(BareKey::Char('x')),
+            vec![Action::CloseTab, Action::SwitchToMode(InputMode::Normal)],
+        ),
+    ];
+    // Closing the only tab would do nothing
+    let tabs = [TabInfo {
+        active: true,
+        ..Default::default()
+    }];
+    let context = HintContext::new(InputMode::Normal, &tabs, &PaneManifest::default());
+    let render = |context_hints| {
+        let options = RenderOptions {
+            context_hints,
+            ..Default::default()
+        };
+        render_hints_for_mode(
+            InputMode::Tab,
+            &keymap,
+            &fixtures::styling(),
+            &options,
+            &context,
+        )
+    };
+    // The text of the dimmed parts and of the others, each run together
+    let split_dimmed = |parts: &[StyledString]| {
+        let (mut dimmed, mut plain) = (String::new(), String::new());
+        for part in parts {
+            let part = part.to_string();
+            let codes = part
+                .strip_prefix("\x1b[")
+                .and_then(|rest| rest.split_once('m'));
+            let is_dimmed =
+                codes.is_some_and(|(codes, _)| codes.split(';').any(|code| code == "2"));
+            let text = strip_ansi(&part);
+            if is_dimmed {
+                dimmed += &text
+            } else {
+                plain += &text
+            }
+        }
+        (dimmed, plain)
+    };
+    let (dimmed, plain) = split_dimmed(&render(ContextHints::Show));
+    assert_eq!((dimmed.trim(), plain.trim()), ("", "n  new   x  close"));
+    let (dimmed, plain) = split_dimmed(&render(ContextHints::Dim));
+    assert_eq!(dimmed.trim(), "x  close");
+    assert_eq!(plain.trim(), "n  new");
+    let hidden = render(ContextHints::Hide);
+    assert_eq!(
+        strip_ansi(&format_line(&hidden, &LineOptions::default())),
+        "   n  new "
+    );
+}
+
+#[test]
+fn frames_and_mouse_toggles_are_listed_last_in_normal_mode() {
+    let keymap = vec![
+        (
+            KeyWithModifier::new(BareKey::Char('m')).with_alt_modifier(),
+            vec![Action::ToggleMouseMode],
+        ),
+        (
+            KeyWithModifier::new(BareKey::Char('p')).with_ctrl_modifier(),
+            vec![Action::SwitchToMode(InputMode::Pane)],
+        ),
+    ];
+    let context = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+    let parts = render_hints_for_mode(
+        InputMode::Normal,
+        &keymap,
+        &Styling::default(),
+        &RenderOptions::default(),
+        &context,
+    );
+    let line = strip_ansi(&format_line(&parts, &LineOptions::default()));
+    assert_eq!(line, "   ctrl  pane   alt-m  mouse ");
+}
+
+#[test]
+fn splits_are_grouped_when_their_keys_are_related() {
+    let split = |key: KeyWithModifier, direction| {
+        (
+            key,
+            vec![
+                Action::NewPane(Some(direction), None, false),
+                Action::SwitchToMode(InputMode::Normal),
+            ],
+        )
+    };
+    let render = |keymap: &[(KeyWithModifier, Vec<Action>)], separate_splits| {
+        let options = RenderOptions {
+            separate_splits,
+            ..Default::default()
+        };
+        let context = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+        let parts = render_hints_for_mode(
+            InputMode::Pane,
+            keymap,
+            &Styling::default(),
+            &options,
+            &context,
+        );
+        strip_ansi(&format_line(&parts, &LineOptions::default()))
+    };
+    let related = [
+        split(KeyWithModifier::new(BareKey::Char('r')), Direction::Right),
+        split(KeyWithModifier::new(BareKey::Char('d')), Direction::Down),
+    ];
+    assert_eq!(render(&related, false), "   r|d  split →↓ ");
+    assert_eq!(render(&related, true), "   r  →   d  ↓ ");
+    // Keys with different modifiers keep a hint each
+    let unrelated = [
+        split(
+            KeyWithModifier::new(BareKey::Char('r')).with_alt_modifier(),
+            Direction::Right,
+        ),
+        split(KeyWithModifier::new(BareKey::Char('d')), Direction::Down),
+    ];
+    assert_eq!(render(&unrelated, false), "   alt-r  →   d  ↓ ");
+}
+
+#[test]
+fn splits_are_grouped_whichever_mode_they_return_to() {
+    // The unlock-first preset's splits end in Locked mode rather than Normal
+    let output = strip_ansi(&render_with(
+        &fixtures::unlock_first_preset(),
+        InputMode::Pane,
+        &RenderOptions::default(),
+    ));
+    assert!(output.contains("r|d  split →↓"), "{output}");
+}
+
+#[test]
+fn esc_cancels_text_entry_apart_from_confirming_it() {
+    let keymap = vec![
+        (
+            KeyWithModifier::new(BareKey::Enter),
+            vec![Action::SwitchToMode(InputMode::Normal)],
+        ),
+        (
+            KeyWithModifier::new(BareKey::Esc),
+            vec![Action::SwitchToMode(InputMode::Normal)],
+        ),
+    ];
+    let context = HintContext::new(InputMode::Normal, &[], &PaneManifest::default());
+    let render = |mode| {
+        let parts = render_hints_for_mode(
+            mode,
+            &keymap,
+            &Styling::default(),
+            &RenderOptions::default(),
+            &context,
+        );
+        strip_ansi(&format_line(&parts, &LineOptions::default()))
+    };
+    assert_eq!(
+        render(InputMode::RenameTab),
+        "   ENTER  normal   ESC  cancel "
+    );
+    // Elsewhere Esc is just another way back
+    assert_eq!(render(InputMode::Tmux), "   ENTER|ESC  normal ");
+}
+
+#[test]
+fn return_hints_lead_back_to_the_previous_mode() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::EnterSearch);
+    let mut context = HintContext::new(fixture.base_mode, &[], &PaneManifest::default());
+    let render = |context: &HintContext| {
+        let parts = render_hints_for_mode(
+            InputMode::EnterSearch,
+            &get_keymap_for_mode(&mode_info),
+            &mode_info.style.colors,
+            &RenderOptions::default(),
+            context,
+        );
+        strip_ansi(&format_line(&parts, &LineOptions::default()))
+    };
+    assert_eq!(render(&context), "   ESC  cancel ");
+    context.previous_mode = Some(InputMode::Scroll);
+    assert_eq!(render(&context), "   ctrl-c  back to scroll   ESC  cancel ");
+}
+
+#[test]
+fn simplified_ui_sticks_to_ascii() {
+    let fixture = fixtures::default_preset();
+    let mode_info = fixture.mode_info(InputMode::Pane);
+    let mut context = HintContext::new(fixture.base_mode, &[], &PaneManifest::default());
+    context.simplified_ui = true;
+    let parts = render_hints_for_mode(
+        InputMode::Pane,
+        &get_keymap_for_mode(&mode_info),
+        &mode_info.style.colors,
+        &RenderOptions::default(),
+        &context,
+    );
+    let pane = strip_ansi(&format_line(&parts, &LineOptions::default()));
+    assert!(pane.is_ascii(), "{:?}", pane);
+    assert!(pane.contains("  r|d  split   c  rename "), "{:?}", pane);
+    assert!(pane.contains("|<|v|Alt v|^|Alt ^|>  move "), "{:?}", pane);
+}
+
+#[test]
+fn min_length_fills_per_alignment() {
+    let parts = [Style::new().paint("ab")];
+    let line = |align| {
+        format_line(
+            &parts,
+            &LineOptions {
+                padding_left: 0,
+                min_length: 7,
+                align,
+                ..Default::default()
+            },
+        )
+    };
+    assert_eq!(line(Align::Left), "ab     ");
+    assert_eq!(line(Align::Center), "  ab   ");
+    assert_eq!(line(Align::Right), "     ab");
+}
+
+#[test]
+fn align_fills_up_to_max_length() {
+    let parts = [Style::new().paint("ab")];
+    let line = |align| {
+        format_line(
+            &parts,
+            &LineOptions {
+                padding_left: 0,
+                max_length: 6,
+                align,
+                ..Default::default()
+            },
+        )
+    };
+    assert_eq!(line(Align::Left), "ab");
+    assert_eq!(line(Align::Center), "  ab  ");
+    assert_eq!(line(Align::Right), "    ab");
+}
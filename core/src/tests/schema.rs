@@ -0,0 +1,96 @@
+use crate::schema::{default_flag, dump_config, option_for_key, OPTIONS};
+use crate::Preset;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn keys_belong_to_the_most_specific_option() {
+    let key = |key: &str| option_for_key(key).map(|option| option.key);
+    assert_eq!(key("max_hints"), Some("max_hints"));
+    assert_eq!(key("pane_max_hints"), Some("<mode>_max_hints"));
+    assert_eq!(key("normal_hints"), Some("<mode>_hints"));
+    assert_eq!(key("pane_hint_frames_fg"), Some("<mode>_hint_<label>_fg"));
+    assert_eq!(key("pane_hint_frames"), Some("<mode>_hint_<label>"));
+    assert_eq!(key("session_hide_about"), Some("<mode>_hide_<hint>"));
+    assert_eq!(key("theme_dark"), Some("theme_<name>"));
+    assert_eq!(key("nonsense_hints"), None);
+    assert_eq!(key("max_lenght"), None);
+}
+
+#[test]
+fn dump_lists_configured_values_and_unknown_keys() {
+    let configuration = BTreeMap::from(
+        [
+            ("max_length", "40"),
+            ("normal_hide_quit", "true"),
+            ("max_lenght", "40"),
+        ]
+        .map(|(key, value)| (key.to_string(), value.to_string())),
+    );
+    let dump = dump_config(&configuration);
+    assert!(dump.contains("\nmax_length \"40\" // number, default: 0\n"));
+    assert!(dump.contains("\n// min_length: number, default: 0\n"));
+    assert!(
+        dump.contains("\n// <mode>_hide_<hint>: bool, default: false\nnormal_hide_quit \"true\"\n")
+    );
+    assert!(dump.ends_with("// unknown options\nmax_lenght \"40\"\n"));
+}
+
+/// Every option the README documents is listed.
+#[test]
+fn options_cover_the_readme() {
+    let readme =
+        fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../README.md")).unwrap();
+    let configuration = readme
+        .split("## Configuration")
+        .nth(1)
+        .and_then(|rest| rest.split("\n## ").next())
+        .unwrap();
+    for line in configuration.lines().filter(|line| line.starts_with("- `")) {
+        let (keys, _) = line.split_once(':').unwrap();
+        // The quoted names before the colon, e.g. `overflow_color` / `overflow_bold`
+        for key in keys.split('`').skip(1).step_by(2) {
+            assert!(
+                OPTIONS.iter().any(|option| option.key == key),
+                "{} isn't in schema::OPTIONS",
+                key
+            );
+        }
+    }
+}
+
+/// The bool defaults the README documents, and those the default style starts from, are the
+/// schema's, which the plugin falls back to for the rest.
+#[test]
+fn bool_defaults_agree_with_the_schema() {
+    let readme =
+        fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../README.md")).unwrap();
+    for line in readme.lines().filter(|line| line.starts_with("- `")) {
+        let (keys, description) = line.split_once(':').unwrap();
+        let [_, key, _] = keys.split('`').collect::<Vec<_>>()[..] else {
+            continue;
+        };
+        let Some(option) = OPTIONS.iter().find(|option| option.key == key) else {
+            continue;
+        };
+        if option.kind == "bool" && description.contains("(default: ") {
+            assert!(
+                description.contains(&format!("(default: {}", option.default)),
+                "{} defaults to {} in schema::OPTIONS",
+                key,
+                option.default
+            );
+        }
+    }
+    let preset = Preset::default();
+    for (key, value) in [
+        ("colors", preset.colors),
+        ("paging", preset.line.paging),
+        ("reverse", preset.render.reverse),
+        ("show_missing", preset.render.show_missing),
+        ("quick_keys", preset.render.quick_keys),
+    ] {
+        assert_eq!(default_flag(key), value, "{}", key);
+    }
+}
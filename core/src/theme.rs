@@ -0,0 +1,75 @@
+//! Named colour overrides for the parts of zellij's theme the hints are drawn with, so they can
+//! follow a theme of their own, e.g. switching between light and dark at runtime.
+
+use crate::style::Colour;
+use zellij_tile::prelude::*;
+
+/// Colours replacing those of zellij's theme; unset ones keep the theme's.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Theme {
+    /// Background of the key pills.
+    pub key_bg: Option<PaletteColor>,
+    /// Text of the key pills.
+    pub key_fg: Option<PaletteColor>,
+    /// Background of the labels.
+    pub label_bg: Option<PaletteColor>,
+    /// Text of the labels and separators.
+    pub label_fg: Option<PaletteColor>,
+    /// The mnemonic letter highlighted in each label.
+    pub highlight: Option<PaletteColor>,
+    /// The locked indicator and the modes of other clients.
+    pub emphasis: Option<PaletteColor>,
+}
+
+impl Theme {
+    /// A theme written as comma-separated assignments, e.g. "key_bg=#1e1e2e, label_fg=white".
+    /// Colours are written as for `Colour::parse`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut theme = Theme::default();
+        for assignment in spec.split(',').filter(|s| !s.trim().is_empty()) {
+            let (slot, value) = assignment
+                .split_once('=')
+                .ok_or_else(|| format!("expected <color>=<value>, got {:?}", assignment.trim()))?;
+            let colour = Colour::parse(value)
+                .map(palette_color)
+                .ok_or_else(|| format!("invalid color: {:?}", value.trim()))?;
+            let slot = match slot.trim() {
+                "key_bg" => &mut theme.key_bg,
+                "key_fg" => &mut theme.key_fg,
+                "label_bg" => &mut theme.label_bg,
+                "label_fg" => &mut theme.label_fg,
+                "highlight" => &mut theme.highlight,
+                "emphasis" => &mut theme.emphasis,
+                other => return Err(format!("unknown theme color: {:?}", other)),
+            };
+            *slot = Some(colour);
+        }
+        Ok(theme)
+    }
+
+    /// Zellij's theme with this one's colours put in the slots the hints are drawn with.
+    pub fn apply(&self, colors: &Styling) -> Styling {
+        let mut colors = *colors;
+        let slots = [
+            (self.key_bg, &mut colors.ribbon_unselected.background),
+            (self.key_fg, &mut colors.ribbon_unselected.base),
+            (self.label_bg, &mut colors.text_unselected.background),
+            (self.label_fg, &mut colors.text_unselected.base),
+            (self.highlight, &mut colors.ribbon_selected.base),
+            (self.emphasis, &mut colors.text_unselected.emphasis_2),
+        ];
+        for (colour, slot) in slots {
+            if let Some(colour) = colour {
+                *slot = colour;
+            }
+        }
+        colors
+    }
+}
+
+fn palette_color(colour: Colour) -> PaletteColor {
+    match colour {
+        Colour::Fixed(n) => PaletteColor::EightBit(n),
+        Colour::RGB(r, g, b) => PaletteColor::Rgb((r, g, b)),
+    }
+}
@@ -0,0 +1,129 @@
+//! Hints spelled out as plain sentences, e.g. for a screen reader or a braille display.
+
+use crate::context::HintContext;
+use crate::hints::collect_hints_for_mode;
+use crate::mode::{mode_name, parse_mode};
+use crate::options::RenderOptions;
+use zellij_tile::prelude::actions::Action;
+use zellij_tile::prelude::*;
+
+/// Describes the hints of `mode` in a sentence, e.g. "Pane mode: press n to open a new pane, x to
+/// close the pane, Enter to select."
+pub fn describe_hints_for_mode(
+    mode: InputMode,
+    keymap: &[(KeyWithModifier, Vec<Action>)],
+    options: &RenderOptions,
+    context: &HintContext,
+) -> String {
+    let hints = collect_hints_for_mode(mode, keymap, &Styling::default(), options, context).hints;
+    let phrases: Vec<String> = hints
+        .iter()
+        .filter(|hint| !hint.keys.is_empty())
+        .map(|hint| {
+            let keys: Vec<String> = hint.keys.iter().map(verbose_key).collect();
+            format!(
+                "{} {}",
+                join_alternatives(&keys),
+                verbose_label(mode, &hint.name, &hint.label)
+            )
+        })
+        .collect();
+
+    let mut name = mode_name(mode).to_string();
+    name[..1].make_ascii_uppercase();
+    if phrases.is_empty() {
+        format!("{} mode.", name)
+    } else {
+        format!("{} mode: press {}.", name, phrases.join(", "))
+    }
+}
+
+/// A key as it would be read out: "Ctrl p", "Left", "Enter", "Space".
+pub fn verbose_key(key: &KeyWithModifier) -> String {
+    let bare = match key.bare_key {
+        BareKey::Left => "Left".to_string(),
+        BareKey::Right => "Right".to_string(),
+        BareKey::Up => "Up".to_string(),
+        BareKey::Down => "Down".to_string(),
+        BareKey::Enter => "Enter".to_string(),
+        BareKey::Esc => "Esc".to_string(),
+        BareKey::PageUp => "Page Up".to_string(),
+        BareKey::PageDown => "Page Down".to_string(),
+        BareKey::Char(' ') => "Space".to_string(),
+        ref other => other.to_string(),
+    };
+    key.key_modifiers
+        .iter()
+        .map(|modifier| modifier.to_string())
+        .chain(std::iter::once(bare))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// "a", "a or b", "a, b or c".
+fn join_alternatives(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [init @ .., last] => format!("{} or {}", init.join(", "), last),
+    }
+}
+
+/// What a hint does, phrased to follow its keys: "to open a new pane", "for tab mode".
+fn verbose_label(mode: InputMode, name: &str, label: &str) -> String {
+    let phrase = match (mode, name) {
+        (InputMode::Normal, "quit") => "to quit",
+        (InputMode::Normal, "new") => "to open a new pane",
+        (InputMode::Normal, "focus") => "to move focus",
+        (InputMode::Normal, "resize") => "to resize the pane",
+        (InputMode::Normal, "layout") => "to switch layouts",
+        (InputMode::Normal, "float") => "to toggle floating panes",
+        (InputMode::Normal, name) if parse_mode(name).is_some() => {
+            return format!("for {} mode", name)
+        }
+        (InputMode::Pane, "new") => "to open a new pane",
+        (InputMode::Pane, "close") => "to close the pane",
+        (InputMode::Pane, "full") => "to toggle fullscreen",
+        (InputMode::Pane, "float") => "to toggle floating panes",
+        (InputMode::Pane, "split_right") => "to split to the right",
+        (InputMode::Pane, "split_down") => "to split downwards",
+        (InputMode::Pane, "split") => "to split to the right or downwards",
+        (InputMode::Pane, "rename") => "to rename the pane",
+        (InputMode::Pane, "move") => "to move focus",
+        (InputMode::Pane, "next") => "to focus the next pane",
+        (InputMode::Pane, "prev") => "to focus the previous pane",
+        (InputMode::Tab, "new") => "to open a new tab",
+        (InputMode::Tab, "close") => "to close the tab",
+        (InputMode::Tab, "break_pane") => "to break the pane out into a new tab",
+        (_, "break_left") => "to move the pane to the tab on the left",
+        (_, "break_right") => "to move the pane to the tab on the right",
+        (InputMode::Tab, "sync") => "to toggle sync",
+        (InputMode::Tab, "rename") => "to rename the tab",
+        (InputMode::Tab, "move") => "to switch tabs",
+        (InputMode::Resize, "resize") => "to resize",
+        (InputMode::Resize, "increase") => "to grow the pane",
+        (InputMode::Resize, "decrease") => "to shrink the pane",
+        (InputMode::Move, "move") => "to move the pane",
+        (_, "search") => "to search",
+        (_, "scroll") => "to scroll",
+        (_, "page") => "to scroll by page",
+        (_, "half_page") => "to scroll by half a page",
+        (_, "pages") => "to scroll by page or by half a page",
+        (_, "edit") => "to edit the scrollback",
+        (_, "clear") => "to clear the screen and scrollback",
+        (_, "frames") => "to toggle pane frames",
+        (_, "mouse") => "to toggle mouse mode",
+        (InputMode::Search, "down") => "to find the next match",
+        (InputMode::Search, "up") => "to find the previous match",
+        (InputMode::Session, "detach") => "to detach",
+        (InputMode::Session, "kill") => "to end the session",
+        (InputMode::Session, "rename") => "to rename the session",
+        (InputMode::Session, "manager") => "for the session manager",
+        (InputMode::Session, "config") => "for the configuration",
+        (InputMode::Session, "plugins") => "for the plugin manager",
+        (InputMode::Session, "about") => "for the about screen",
+        (_, name) if parse_mode(name).is_some() => return format!("for {} mode", name),
+        _ => return format!("to {}", label),
+    };
+    phrase.to_string()
+}
@@ -0,0 +1,58 @@
+//! Feeds arbitrary input to the escape sequence parser and checks that it always terminates,
+//! never loses or invents input, and that measuring and truncating agree with each other.
+//!
+//! Run with `cargo +nightly fuzz run ansi_parser` from the repository root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use unicode_width::UnicodeWidthChar;
+
+use zjstatus_hints_core::ansi::{
+    calculate_visible_length, truncate_ansi_string, AnsiParser, AnsiSegment,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&max_len, rest)) = data.split_first() else {
+        return;
+    };
+    let max_len = max_len as usize;
+    let text = String::from_utf8_lossy(rest);
+    let overflow = "…";
+
+    let mut rebuilt = String::new();
+    let mut visible = 0;
+    let mut parser = AnsiParser::new(&text);
+    while let Some(segment) = parser.next_segment() {
+        match segment {
+            AnsiSegment::EscapeSequence(seq) => {
+                assert!(!seq.is_empty(), "empty escape segment");
+                rebuilt.push_str(&seq);
+            }
+            AnsiSegment::VisibleChar(ch) => {
+                assert!(
+                    !ch.is_control(),
+                    "control character {:?} counted as visible",
+                    ch
+                );
+                rebuilt.push(ch);
+                visible += UnicodeWidthChar::width(ch).unwrap_or(0);
+            }
+        }
+    }
+    assert_eq!(rebuilt, text, "parser dropped or invented input");
+    assert_eq!(calculate_visible_length(&text), visible);
+
+    let truncated = truncate_ansi_string(&text, overflow, max_len);
+    let truncated_len = calculate_visible_length(&truncated);
+    if visible <= max_len {
+        assert_eq!(truncated, text);
+    } else {
+        assert!(
+            truncated_len <= max_len.max(1),
+            "truncated to {} columns, limit was {}",
+            truncated_len,
+            max_len
+        );
+    }
+});
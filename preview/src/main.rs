@@ -0,0 +1,209 @@
+//! Prints the hint line zjstatus-hints renders for each mode of a zellij config, so labels and
+//! bindings can be checked without rebuilding the plugin and reloading zellij after every tweak.
+//!
+//! The config is merged onto zellij's defaults the same way zellij loads it, so a config that
+//! only overrides a few bindings previews the keymap zellij would actually use. The plugin's
+//! options come from its block in the config and `--option`, and the colors from the config's
+//! theme, so the lines are those the plugin would send.
+
+use std::collections::BTreeMap;
+use std::process::ExitCode;
+use zellij_utils::data::{InputMode, ModeInfo, PaneManifest, Style};
+use zellij_utils::input::config::Config;
+use zellij_utils::input::layout::{RunPlugin, RunPluginOrAlias};
+use zjstatus_hints_core::ansi::strip_ansi;
+use zjstatus_hints_core::config;
+use zjstatus_hints_core::mode::takes_text;
+use zjstatus_hints_core::{
+    base_mode_parts, collect_hints_for_mode, describe_hints_for_mode, fit_hints, format_line,
+    get_keymap_for_mode, mode_name, parse_mode, render_hint_page, HintContext, ALL_MODES,
+};
+
+const USAGE: &str = "\
+usage: zjstatus-hints-preview [options] [config.kdl]
+
+Prints the hints for every mode of the given zellij config (zellij's defaults if omitted),
+with the options of the zjstatus-hints plugin configured there (in `plugins` or `load_plugins`)
+and the config's theme.
+
+options:
+  --mode <mode>        only preview this mode (e.g. pane, tab, entersearch)
+  --option <key=value> set a plugin option, e.g. for one set in a layout; may be repeated
+  --max-length <n>     truncate like the plugin's max_length option
+  --plain              print without colors
+  --verbose            print the sentences sent to the plugin's verbose_pipe_name
+  -h, --help           show this message";
+
+#[derive(Default)]
+struct Args {
+    config: Option<String>,
+    mode: Option<InputMode>,
+    /// Plugin options set on the command line, over those in the config.
+    options: BTreeMap<String, String>,
+    plain: bool,
+    verbose: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(String::new()),
+            "--plain" => args.plain = true,
+            "--verbose" => args.verbose = true,
+            "--mode" => {
+                let value = argv.next().ok_or("--mode needs a value")?;
+                args.mode = Some(parse_mode(&value).ok_or(format!("unknown mode: {}", value))?);
+            }
+            "--option" => {
+                let value = argv.next().ok_or("--option needs a value")?;
+                let (key, value) = value
+                    .split_once('=')
+                    .ok_or(format!("expected --option <key>=<value>, got {}", value))?;
+                args.options
+                    .insert(key.trim().to_string(), value.trim().to_string());
+            }
+            "--max-length" => {
+                let value = argv.next().ok_or("--max-length needs a value")?;
+                value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid --max-length: {}", value))?;
+                args.options.insert("max_length".to_string(), value);
+            }
+            flag if flag.starts_with('-') => return Err(format!("unknown option: {}", flag)),
+            path if args.config.is_none() => args.config = Some(path.to_string()),
+            extra => return Err(format!("unexpected argument: {}", extra)),
+        }
+    }
+    Ok(args)
+}
+
+fn load_config(path: Option<&str>) -> Result<Config, String> {
+    let defaults = Config::from_default_assets()
+        .map_err(|e| format!("failed to load zellij's default config: {}", e))?;
+    let Some(path) = path else {
+        return Ok(defaults);
+    };
+    let kdl = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    Config::from_kdl(&kdl, Some(defaults)).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// The options of the zjstatus-hints plugin in `config`, going by the plugin's URL: those of a
+/// `load_plugins` entry, or else of an alias in `plugins`, with the alias's own on top when a
+/// `load_plugins` entry names it.
+fn plugin_configuration(config: &Config) -> BTreeMap<String, String> {
+    let is_hints = |plugin: &RunPlugin| plugin.location.to_string().contains("zjstatus-hints");
+    let loaded = config
+        .background_plugins
+        .iter()
+        .filter_map(|plugin| match plugin {
+            RunPluginOrAlias::RunPlugin(plugin) => {
+                is_hints(plugin).then(|| plugin.configuration.inner().clone())
+            }
+            RunPluginOrAlias::Alias(alias) => {
+                let plugin = config.plugins.aliases.get(&alias.name)?;
+                let mut configuration = plugin.configuration.clone();
+                if let Some(own) = &alias.configuration {
+                    configuration.merge(own.inner());
+                }
+                is_hints(plugin).then(|| configuration.inner().clone())
+            }
+        });
+    let aliased = config
+        .plugins
+        .aliases
+        .values()
+        .filter(|plugin| is_hints(plugin))
+        .map(|plugin| plugin.configuration.inner().clone());
+    loaded.chain(aliased).next().unwrap_or_default()
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            if !message.is_empty() {
+                eprintln!("{}\n", message);
+            }
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let config = match load_config(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut configuration = plugin_configuration(&config);
+    configuration.extend(args.options);
+    let options = config::render_options(&configuration);
+    let line_options = config::line_options(&configuration);
+    let base_mode_display = config::base_mode_display(&configuration);
+    let hide_in_text_entry = config::flag(&configuration, "hide_in_text_entry");
+    let plain = args.plain || !config::colors(&configuration);
+
+    let base_mode = config.options.default_mode.unwrap_or(InputMode::Normal);
+    let keybinds = config.keybinds.to_keybinds_vec();
+    // Zellij's theme, with the plugin's selected `theme_<name>` on top
+    let colors = config
+        .theme_config(config.options.theme.as_ref())
+        .unwrap_or_default();
+    let themes = config::themes(&configuration);
+    let colors = match configuration.get("theme").and_then(|name| themes.get(name)) {
+        Some(theme) => theme.apply(&colors),
+        None => colors,
+    };
+    let modes = match args.mode {
+        Some(mode) => vec![mode],
+        None => ALL_MODES
+            .into_iter()
+            .filter(|mode| keybinds.iter().any(|(bound, _)| bound == mode))
+            .collect(),
+    };
+
+    for mode in modes {
+        let mode_info = ModeInfo {
+            mode,
+            base_mode: Some(base_mode),
+            keybinds: keybinds.clone(),
+            style: Style {
+                colors,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let keymap = get_keymap_for_mode(&mode_info);
+        let context = HintContext::new(base_mode, &[], &PaneManifest::default());
+        if args.verbose {
+            println!(
+                "{}",
+                describe_hints_for_mode(mode, &keymap, &options, &context)
+            );
+            continue;
+        }
+        // As the plugin draws it, on the first page and without a session to go by
+        let display = base_mode_display.in_mode(mode, base_mode, &options);
+        let line_options = line_options.for_mode(mode);
+        let hints = || {
+            let mode_hints = collect_hints_for_mode(mode, &keymap, &colors, &options, &context);
+            if line_options.paging {
+                render_hint_page(mode_hints, 0, &colors, &options, &line_options)
+            } else {
+                fit_hints(mode_hints, &colors, &options, &line_options)
+            }
+        };
+        let line = if hide_in_text_entry && takes_text(mode) {
+            String::new()
+        } else {
+            let parts = base_mode_parts(display, mode, hints, None, &colors);
+            format_line(&parts, &line_options)
+        };
+        let line = if plain { strip_ansi(&line) } else { line };
+        println!("{:<12}{}", mode_name(mode), line);
+    }
+    ExitCode::SUCCESS
+}
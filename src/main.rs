@@ -1,123 +1,256 @@
-use ansi_term::{
-    ANSIString, ANSIStrings,
-    Colour::{Fixed, RGB},
-    Style,
-};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use zellij_tile::prelude::actions::Action;
-use zellij_tile::prelude::actions::SearchDirection;
 use zellij_tile::prelude::*;
-use zellij_tile_utils::palette_match;
+use zjstatus_hints_core::actions::parse_action_patterns;
+use zjstatus_hints_core::ansi::{calculate_visible_length, skip_columns, strip_ansi};
+use zjstatus_hints_core::components::hints_to_ribbons;
+use zjstatus_hints_core::config::{self, flag};
+use zjstatus_hints_core::mode::takes_text;
+use zjstatus_hints_core::schema::dump_config;
+use zjstatus_hints_core::{
+    add_other_client_modes, base_mode_parts, collect_hints_for_mode, describe_hints_for_mode,
+    fit_hints, format_line, get_keymap_for_mode, keymap_hints, mode_name, parse_mode,
+    render_hint_page, ActionMatch, BaseModeDisplay, CustomHint, HintContext, HintStyle,
+    LineOptions, RenderMode, RenderOptions, Theme,
+};
 
 #[derive(Default)]
 struct State {
     initialized: bool,
+    /// The options as configured, with those changed at runtime updated, for `dump-config`.
+    configuration: BTreeMap<String, String>,
     pipe_name: String,
+    /// Whether the hints are sent to zjstatus and drawn in the plugin's own pane.
+    pipe_output: bool,
+    print_to_pane: bool,
+    /// Only pipe the hints when asked on `REQUEST_PIPE`, not on every update.
+    pipe_on_request: bool,
     mode_info: ModeInfo,
-    base_mode_is_locked: bool,
-    max_length: usize,
-    overflow_str: String,
-    hide_in_base_mode: bool,
+    line_options: LineOptions,
+    /// Template the hints are put in, see `HintContext::expand`.
+    format: Option<String>,
+    base_mode_display: BaseModeDisplay,
+    /// Send nothing while renaming or entering a search, see `takes_text`.
+    hide_in_text_entry: bool,
+    colors: bool,
+    render_options: RenderOptions,
+    tabs: Vec<TabInfo>,
+    panes: PaneManifest,
+    context: HintContext,
+    show_other_clients: bool,
+    /// The client this instance renders for, from the first ListClients event; unknown until
+    /// then.
+    client_id: Option<ClientId>,
+    other_client_modes: BTreeMap<ClientId, InputMode>,
+    render_cache: Option<(RenderCacheKey, String)>,
+    last_output: Option<String>,
+    pipe_interval: Duration,
+    last_pipe_sent: Option<Instant>,
+    pending_pipe_output: Option<String>,
+    pipe_timer_set: bool,
+    show_delay: Duration,
+    auto_hide_after: Duration,
+    /// How long the hints stay in the selected ribbon colors after a mode change.
+    flash_duration: Duration,
+    mode_entered: Option<Instant>,
+    show_pending: bool,
+    idle_hidden: bool,
+    flashing: bool,
+    /// Whether the raw keymap is shown instead of the hints, see `toggle_keymap`.
+    show_keymap: bool,
+    debug: bool,
+    debug_file: Option<PathBuf>,
+    permission_denied: bool,
+    verbose_pipe_name: Option<String>,
+    width_pipe_name: Option<String>,
+    render_mode: RenderMode,
+    themes: BTreeMap<String, Theme>,
+    theme: Option<String>,
+    /// The page of hints shown when `paging` splits them, moved by the page commands.
+    page: isize,
+    mouse_scroll: bool,
+    /// Columns of the hints scrolled out of view to the left of the plugin's pane.
+    scroll_offset: usize,
+    /// Width of the plugin's pane at the last render.
+    cols: usize,
+    last_verbose: Option<String>,
+    /// Further zjstatus pipes, each carrying a selection of the hints.
+    extra_pipes: Vec<ExtraPipe>,
+    /// Ids of the zjstatus panes in the last PaneUpdate.
+    zjstatus_panes: Vec<u32>,
+    /// Only pipe while there's a zjstatus pane to receive the hints.
+    require_zjstatus: bool,
+    /// The custom hints of the configuration, which clearing the overrides goes back to.
+    configured_custom_hints: HashMap<InputMode, Vec<CustomHint>>,
 }
 
-register_plugin!(State);
+/// A further zjstatus pipe carrying some of the hints, e.g. the modal modes' hints for a top bar
+/// while the main pipe feeds a bottom one.
+struct ExtraPipe {
+    name: String,
+    /// The modes it shows hints in; it's empty in the others.
+    modes: Option<Vec<InputMode>>,
+    /// The hints it shows, picked and ordered as by `<mode>_hints`.
+    hints: Option<Vec<String>>,
+}
 
-const TO_NORMAL: Action = Action::SwitchToMode(InputMode::Normal);
+impl ExtraPipe {
+    /// A pipe configured as `extra_pipe_<name>`, with comma-separated assignments such as
+    /// "modes=pane tab, hints=new focus". Unset, it shows every mode or every hint.
+    fn parse(name: &str, spec: &str) -> Result<Self, String> {
+        let mut pipe = ExtraPipe {
+            name: name.to_string(),
+            modes: None,
+            hints: None,
+        };
+        for assignment in spec.split(',').filter(|s| !s.trim().is_empty()) {
+            let (setting, value) = assignment.split_once('=').ok_or_else(|| {
+                format!("expected <setting>=<value>, got {:?}", assignment.trim())
+            })?;
+            let words = value.split_whitespace();
+            match setting.trim() {
+                "modes" => {
+                    let modes = words
+                        .map(|mode| {
+                            parse_mode(mode).ok_or_else(|| format!("unknown mode {:?}", mode))
+                        })
+                        .collect::<Result<_, _>>()?;
+                    pipe.modes = Some(modes);
+                }
+                "hints" => pipe.hints = Some(words.map(str::to_string).collect()),
+                other => return Err(format!("unknown setting {:?}", other)),
+            }
+        }
+        Ok(pipe)
+    }
+}
 
-const PLUGIN_SESSION_MANAGER: &str = "session-manager";
-const PLUGIN_CONFIGURATION: &str = "configuration";
-const PLUGIN_MANAGER: &str = "plugin-manager";
-const PLUGIN_ABOUT: &str = "zellij:about";
+/// Everything the rendered output depends on besides the configuration, which only changes
+/// through `COMMAND_PIPE` and clears the cache when it does.
+#[derive(PartialEq)]
+struct RenderCacheKey {
+    mode: InputMode,
+    keymap: Vec<(KeyWithModifier, Vec<Action>)>,
+    colors: Styling,
+    context: HintContext,
+    other_client_modes: BTreeMap<ClientId, InputMode>,
+}
 
-const KEY_PATTERNS_NO_SEPARATOR: &[&str] = &["HJKL", "hjkl", "←↓↑→", "←→", "↓↑", "[]"];
+register_plugin!(State);
 
-const DEFAULT_MAX_LENGTH: usize = 0;
-const DEFAULT_OVERFLOW_STR: &str = "...";
 const DEFAULT_PIPE_NAME: &str = "zjstatus_hints";
+const DEFAULT_PIPE_INTERVAL_MS: u64 = 0;
 
-type ActionLabel = (Action, &'static str);
-type ActionSequenceLabel = (&'static [Action], &'static str);
-
-const NORMAL_MODE_ACTIONS: &[ActionLabel] = &[
-    (Action::SwitchToMode(InputMode::Pane), "pane"),
-    (Action::SwitchToMode(InputMode::Tab), "tab"),
-    (Action::SwitchToMode(InputMode::Resize), "n-resize"),
-    (Action::SwitchToMode(InputMode::Move), "h-move"),
-    (Action::SwitchToMode(InputMode::Scroll), "scroll"),
-    (Action::SwitchToMode(InputMode::Search), "search"),
-    (Action::SwitchToMode(InputMode::Session), "o-session"),
-    (Action::Quit, "quit"),
-];
-
-const PANE_MODE_ACTION_SEQUENCES: &[ActionSequenceLabel] = &[
-    (&[Action::NewPane(None, None, false), TO_NORMAL], "new"),
-    (&[Action::CloseFocus, TO_NORMAL], "x"),
-    (&[Action::ToggleFocusFullscreen, TO_NORMAL], "full"),
-    (&[Action::ToggleFloatingPanes, TO_NORMAL], "float"),
-    (
-        &[
-            Action::NewPane(Some(Direction::Right), None, false),
-            TO_NORMAL,
-        ],
-        "→",
-    ),
-    (
-        &[
-            Action::NewPane(Some(Direction::Down), None, false),
-            TO_NORMAL,
-        ],
-        "↓",
-    ),
-];
-
-const TAB_MODE_ACTION_SEQUENCES: &[ActionSequenceLabel] = &[
-    (
-        &[
-            Action::NewTab(None, vec![], None, None, None, true),
-            TO_NORMAL,
-        ],
-        "new",
-    ),
-    (&[Action::CloseTab, TO_NORMAL], "close"),
-    (&[Action::BreakPane, TO_NORMAL], "break pane"),
-    (&[Action::ToggleActiveSyncTab, TO_NORMAL], "sync"),
-];
-
-fn get_common_modifiers(mut key_bindings: Vec<&KeyWithModifier>) -> Vec<KeyModifier> {
-    if key_bindings.is_empty() {
-        return vec![];
-    }
-    let mut common_modifiers = key_bindings.pop().unwrap().key_modifiers.clone();
-    for key in key_bindings {
-        common_modifiers = common_modifiers
-            .intersection(&key.key_modifiers)
-            .cloned()
-            .collect();
-    }
-    common_modifiers.into_iter().collect()
-}
+/// Shown instead of the hints when the user declines the permission request.
+const PERMISSION_DENIED_MESSAGE: &str = " zjstatus-hints can't show hints without the ReadApplicationState and MessageAndLaunchOtherPlugins permissions; reload the plugin to be asked again ";
+
+/// Pipe used by the instances running for each connected client to share their current mode.
+const CLIENT_MODE_PIPE: &str = "zjstatus_hints::client_mode";
+
+/// Pipe for changing options at runtime, e.g. `zellij pipe --name zjstatus_hints::command --
+/// "set max_length=40"`.
+const COMMAND_PIPE: &str = "zjstatus_hints::command";
+
+/// Pipe asking for the current hints, e.g. `zellij pipe --name zjstatus_hints::request`, which
+/// prints them. Plugins asking get them back as a message on `RESPONSE_PIPE`.
+const REQUEST_PIPE: &str = "zjstatus_hints::request";
+const RESPONSE_PIPE: &str = "zjstatus_hints::response";
+
+/// Pipe through which other plugins and scripts add hints and relabel existing ones until they
+/// clear them, e.g. `zellij pipe --name zjstatus_hints::override -- "label pane split=split"`.
+const OVERRIDE_PIPE: &str = "zjstatus_hints::override";
+
+/// Columns the hints move per line of mouse-wheel scrolling with `mouse_scroll`.
+const SCROLL_COLUMNS: usize = 4;
 
 impl ZellijPlugin for State {
     fn load(&mut self, configuration: BTreeMap<String, String>) {
         self.initialized = false;
+        self.configuration = configuration.clone();
 
         // TODO: configuration validation
-        self.max_length = configuration
-            .get("max_length")
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(DEFAULT_MAX_LENGTH);
-        self.overflow_str = configuration
-            .get("overflow_str")
-            .cloned()
-            .unwrap_or_else(|| DEFAULT_OVERFLOW_STR.to_string());
+        self.line_options = config::line_options(&configuration);
+        self.format = configuration
+            .get("format")
+            .filter(|s| !s.is_empty())
+            .cloned();
         self.pipe_name = configuration
             .get("pipe_name")
             .cloned()
             .unwrap_or_else(|| DEFAULT_PIPE_NAME.to_string());
-        self.hide_in_base_mode = configuration
-            .get("hide_in_base_mode")
-            .map(|s| s.to_lowercase().parse::<bool>().unwrap_or(false))
-            .unwrap_or(false);
+        self.pipe_output = flag(&configuration, "pipe_output");
+        self.print_to_pane = flag(&configuration, "print_to_pane");
+        self.require_zjstatus = flag(&configuration, "require_zjstatus");
+        self.pipe_on_request = flag(&configuration, "pipe_on_request");
+        self.base_mode_display = config::base_mode_display(&configuration);
+        self.hide_in_text_entry = flag(&configuration, "hide_in_text_entry");
+        self.render_options = config::render_options(&configuration);
+        self.configured_custom_hints = self.render_options.custom_hints.clone();
+
+        self.colors = config::colors(&configuration);
+
+        self.show_other_clients = flag(&configuration, "show_other_clients");
+        self.pipe_interval = Duration::from_millis(
+            configuration
+                .get("pipe_interval_ms")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_PIPE_INTERVAL_MS),
+        );
+        self.show_delay = Duration::from_millis(
+            configuration
+                .get("show_delay_ms")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+        );
+        self.auto_hide_after = Duration::from_secs_f64(
+            configuration
+                .get("auto_hide_after")
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|secs| secs.is_finite() && *secs > 0.0)
+                .unwrap_or(0.0),
+        );
+        self.flash_duration = Duration::from_millis(
+            configuration
+                .get("flash_ms")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+        );
+        self.debug = flag(&configuration, "debug");
+        self.debug_file = configuration.get("debug_file").map(PathBuf::from);
+        self.render_mode = configuration
+            .get("render_mode")
+            .and_then(|s| RenderMode::parse(s))
+            .unwrap_or_default();
+        self.themes = config::themes(&configuration);
+        self.theme = configuration
+            .get("theme")
+            .filter(|name| self.themes.contains_key(*name))
+            .cloned();
+        self.mouse_scroll = flag(&configuration, "mouse_scroll");
+        self.verbose_pipe_name = configuration
+            .get("verbose_pipe_name")
+            .filter(|s| !s.is_empty())
+            .cloned();
+        self.extra_pipes = configuration
+            .iter()
+            .filter_map(|(key, value)| {
+                let name = key.strip_prefix("extra_pipe_")?;
+                match ExtraPipe::parse(name, value) {
+                    Ok(pipe) => Some(pipe),
+                    Err(error) => {
+                        eprintln!("zjstatus-hints: {}: {}", key, error);
+                        None
+                    }
+                }
+            })
+            .collect();
+        self.width_pipe_name = configuration
+            .get("width_pipe_name")
+            .filter(|s| !s.is_empty())
+            .cloned();
 
         request_permission(&[
             PermissionType::ReadApplicationState,
@@ -125,685 +258,739 @@ impl ZellijPlugin for State {
         ]);
 
         set_selectable(false);
-        subscribe(&[EventType::ModeUpdate, EventType::SessionUpdate]);
+        subscribe(&[
+            EventType::ModeUpdate,
+            EventType::SessionUpdate,
+            EventType::TabUpdate,
+            EventType::PaneUpdate,
+            EventType::Timer,
+            EventType::PermissionRequestResult,
+            EventType::ListClients,
+        ]);
+        if self.mouse_scroll {
+            subscribe(&[EventType::Mouse]);
+        }
     }
 
     fn update(&mut self, event: Event) -> bool {
-        let mut should_render = !self.initialized;
-        if let Event::ModeUpdate(mode_info) = event {
-            if self.mode_info != mode_info {
-                should_render = true;
+        self.debug_log(|| format!("event: {}", describe_event(&event)));
+        match event {
+            Event::ModeUpdate(mode_info) => {
+                if self.show_other_clients && self.mode_info.mode != mode_info.mode {
+                    self.broadcast_client_mode(&mode_info);
+                }
+                if self.mode_entered.is_none() || self.mode_info.mode != mode_info.mode {
+                    // Not when the plugin starts, which isn't a change the user made
+                    self.flashing = self.mode_entered.is_some() && !self.flash_duration.is_zero();
+                    self.context.previous_mode = self.mode_entered.map(|_| self.mode_info.mode);
+                    self.mode_entered = Some(Instant::now());
+                    self.show_pending =
+                        !self.show_delay.is_zero() && mode_info.mode != base_mode(&mode_info);
+                    self.idle_hidden = false;
+                    self.page = 0;
+                    self.scroll_offset = 0;
+                    self.check_mode_timers();
+                }
+                self.context.base_mode = base_mode(&mode_info);
+                // Renaming and searching count as the modes they're entered from
+                let used_mode = match mode_info.mode {
+                    InputMode::RenameTab => InputMode::Tab,
+                    InputMode::RenamePane => InputMode::Pane,
+                    InputMode::EnterSearch => InputMode::Scroll,
+                    mode => mode,
+                };
+                if ![InputMode::Normal, self.context.base_mode].contains(&used_mode) {
+                    self.context.last_mode = Some(used_mode);
+                }
+                self.context.simplified_ui = mode_info.capabilities.arrow_fonts;
+                self.mode_info = mode_info;
+            }
+            Event::SessionUpdate(sessions, _) => {
+                let alone = sessions
+                    .iter()
+                    .find(|session| session.is_current_session)
+                    .is_some_and(|session| session.connected_clients <= 1);
+                if alone {
+                    self.other_client_modes.clear();
+                }
+            }
+            Event::TabUpdate(tabs) => {
+                self.tabs = tabs;
+                self.update_context();
+            }
+            Event::PaneUpdate(panes) => {
+                let zjstatus_panes = zjstatus_panes(&panes);
+                // A zjstatus pane that wasn't there before was just (re)loaded and starts out
+                // empty, so retry sending as at startup
+                if zjstatus_panes
+                    .iter()
+                    .any(|id| !self.zjstatus_panes.contains(id))
+                {
+                    self.debug_log(|| format!("zjstatus loaded: panes {:?}", zjstatus_panes));
+                    self.initialized = false;
+                    self.last_pipe_sent = None;
+                }
+                self.zjstatus_panes = zjstatus_panes;
+                self.panes = panes;
+                self.update_context();
+            }
+            Event::PermissionRequestResult(status) => {
+                self.permission_denied = status == PermissionStatus::Denied;
+                if self.permission_denied {
+                    eprintln!("zjstatus-hints:{}", PERMISSION_DENIED_MESSAGE.trim_end());
+                } else {
+                    // Plugins aren't told their client directly, so ask which one is current
+                    list_clients();
+                }
             }
-            self.mode_info = mode_info;
-            self.base_mode_is_locked = self.mode_info.base_mode == Some(InputMode::Locked);
+            Event::ListClients(clients) => {
+                let client_id = clients
+                    .iter()
+                    .find(|client| client.is_current_client)
+                    .map(|client| client.client_id);
+                if client_id.is_some() && client_id != self.client_id {
+                    self.client_id = client_id;
+                    // Pipes named after the client can be sent to now
+                    return true;
+                }
+            }
+            Event::Timer(_) => {
+                self.pipe_timer_set = false;
+                if let Some(output) = self.pending_pipe_output.take() {
+                    self.send_pipe(output);
+                }
+                self.check_mode_timers();
+            }
+            Event::Mouse(mouse) => return self.scroll(mouse),
+            _ => {}
         };
-        should_render
+        // Keep rendering (and thereby re-sending) until initialized, see `render`.
+        !self.initialized || self.output_changed()
     }
 
-    fn render(&mut self, _rows: usize, _cols: usize) {
-        let mode_info = &self.mode_info;
-        let output = if !(self.hide_in_base_mode && Some(mode_info.mode) == mode_info.base_mode) {
-            let keymap = get_keymap_for_mode(mode_info);
-            let parts = render_hints_for_mode(mode_info.mode, &keymap, &mode_info.style.colors);
-
-            let ansi_strings = ANSIStrings(&parts);
-            let formatted = format!(" {}", ansi_strings);
-
-            let visible_len = calculate_visible_length(&formatted);
-            if self.max_length > 0 && visible_len > self.max_length {
-                truncate_ansi_string(&formatted, &self.overflow_str, self.max_length)
-            } else {
-                formatted.to_string()
+    fn pipe(&mut self, pipe_message: PipeMessage) -> bool {
+        self.debug_log(|| {
+            format!(
+                "pipe: {} {:?}",
+                pipe_message.name,
+                pipe_message.payload.as_deref().unwrap_or("")
+            )
+        });
+        if pipe_message.name == CLIENT_MODE_PIPE && self.show_other_clients {
+            if let Some(payload) = pipe_message.payload {
+                self.receive_client_mode(&payload);
             }
-        } else {
-            String::new()
-        };
+            return self.output_changed();
+        }
+        if pipe_message.name == REQUEST_PIPE {
+            let plain = pipe_message.payload.as_deref().map(str::trim) == Some("plain");
+            self.answer_request(pipe_message.source, plain);
+            return false;
+        }
+        if pipe_message.name == OVERRIDE_PIPE {
+            if let Some(payload) = pipe_message.payload {
+                for line in payload.lines().filter(|line| !line.trim().is_empty()) {
+                    if let Err(error) = self.apply_override(line) {
+                        eprintln!("zjstatus-hints: {}", error);
+                    }
+                }
+                self.render_cache = None;
+            }
+            return self.output_changed();
+        }
+        if pipe_message.name == COMMAND_PIPE {
+            if let Some(payload) = pipe_message.payload {
+                self.run_command(&payload, &pipe_message.source);
+            }
+            return self.output_changed();
+        }
+        false
+    }
+
+    fn render(&mut self, _rows: usize, cols: usize) {
+        self.cols = cols;
+        let output = self.cached_output();
+        self.last_output = Some(output.clone());
+        let mode_info = &self.mode_info;
 
         // HACK: Because we're not sure when zjstatus will be ready to receive messages,
         // we'll repeatedly send messages until the user has switched to a different mode,
         // at which point we'll assume that zjstatus has been initialized. The render function
         // does not seem to be called too frequently, so this should be fine.
-        if !output.is_empty() && Some(mode_info.mode) != mode_info.base_mode {
+        if !output.is_empty() && mode_info.mode != base_mode(mode_info) {
             self.initialized = true;
         }
 
-        pipe_message_to_plugin(MessageToPlugin::new("pipe").with_payload(format!(
-            "zjstatus::pipe::pipe_{}::{}",
-            self.pipe_name, output
-        )));
-        print!("{}", output);
+        // Without the permissions piping fails too, so the message only shows in the plugin's
+        // own pane and the log, even if the pane is otherwise left blank.
+        if self.render_mode == RenderMode::Components
+            && !output.is_empty()
+            && !self.permission_denied
+        {
+            if self.print_to_pane {
+                print!("{}", self.components_output());
+            }
+        } else if self.print_to_pane || self.permission_denied {
+            print!("{}", skip_columns(&output, self.scroll_offset));
+        }
+        if !self.permission_denied {
+            if self.pipe_output && !self.pipe_on_request {
+                self.send_pipe(output);
+            }
+            self.send_verbose();
+        }
     }
 }
 
-struct AnsiParser<'a> {
-    chars: std::iter::Peekable<std::str::Chars<'a>>,
-}
-
-impl<'a> AnsiParser<'a> {
-    fn new(text: &'a str) -> Self {
-        Self {
-            chars: text.chars().peekable(),
+impl State {
+    /// Pipes the output, holding it back while within `pipe_interval` of the previous send.
+    /// Held-back output is sent by the next Timer event, so rapid mode changes coalesce into
+    /// one message carrying the latest output.
+    fn send_pipe(&mut self, output: String) {
+        // Sending resumes once a zjstatus pane shows up, see the PaneUpdate event
+        if self.require_zjstatus && self.zjstatus_panes.is_empty() {
+            self.debug_log(|| "no zjstatus pane open, not piping the hints".to_string());
+            self.pending_pipe_output = None;
+            return;
         }
-    }
-
-    fn next_segment(&mut self) -> Option<AnsiSegment> {
-        let ch = self.chars.next()?;
-
-        if ch == '\x1b' {
-            let mut escape_seq = String::from(ch);
-            for escape_ch in self.chars.by_ref() {
-                escape_seq.push(escape_ch);
-                if escape_ch == 'm' {
-                    break;
+        if let Some(elapsed) = self.last_pipe_sent.map(|sent| sent.elapsed()) {
+            if elapsed < self.pipe_interval {
+                self.pending_pipe_output = Some(output);
+                if !self.pipe_timer_set {
+                    set_timeout((self.pipe_interval - elapsed).as_secs_f64());
+                    self.pipe_timer_set = true;
                 }
+                return;
             }
-            Some(AnsiSegment::EscapeSequence(escape_seq))
-        } else {
-            Some(AnsiSegment::VisibleChar(ch))
+        }
+        self.last_pipe_sent = Some(Instant::now());
+        self.pending_pipe_output = None;
+        let visible_length = calculate_visible_length(&output);
+        self.debug_log(|| {
+            format!(
+                "pipe send: {} visible chars, {} bytes",
+                visible_length,
+                output.len()
+            )
+        });
+
+        // Every client runs its own instance of this plugin, and broadcasts reach the consumers
+        // of all clients, so tag the payload with the client it was rendered for. zjstatus
+        // doesn't filter on it, so its bars show whichever client's hints came last. The width is
+        // passed along so consumers sizing neighbouring widgets needn't parse the escapes.
+        let mut args = self.client_args();
+        args.insert("visible_length".to_string(), visible_length.to_string());
+        pipe_message_to_plugin(
+            MessageToPlugin::new("pipe")
+                .with_payload(format!(
+                    "zjstatus::pipe::pipe_{}::{}",
+                    self.pipe_name, output
+                ))
+                .with_args(args.clone()),
+        );
+        for pipe in &self.extra_pipes {
+            let output = self.extra_pipe_output(pipe, &output);
+            let mut args = self.client_args();
+            args.insert(
+                "visible_length".to_string(),
+                calculate_visible_length(&output).to_string(),
+            );
+            pipe_message_to_plugin(
+                MessageToPlugin::new("pipe")
+                    .with_payload(format!("zjstatus::pipe::pipe_{}::{}", pipe.name, output))
+                    .with_args(args),
+            );
+        }
+        if let Some(width_pipe_name) = &self.width_pipe_name {
+            pipe_message_to_plugin(
+                MessageToPlugin::new("pipe")
+                    .with_payload(format!(
+                        "zjstatus::pipe::pipe_{}::{}",
+                        width_pipe_name, visible_length
+                    ))
+                    .with_args(args),
+            );
         }
     }
-}
 
-enum AnsiSegment {
-    EscapeSequence(String),
-    VisibleChar(char),
-}
-
-fn calculate_visible_length(text: &str) -> usize {
-    let mut parser = AnsiParser::new(text);
-    let mut len = 0;
-
-    while let Some(segment) = parser.next_segment() {
-        if matches!(segment, AnsiSegment::VisibleChar(_)) {
-            len += 1;
+    /// Answers a request for the current hints: the CLI gets them as the pipe's output, a plugin
+    /// as a message back, and a keybinding has them sent to zjstatus as on any update.
+    fn answer_request(&mut self, source: PipeSource, plain: bool) {
+        let mut output = self.cached_output();
+        if plain {
+            output = strip_ansi(&output);
+        }
+        self.debug_log(|| format!("request from {:?}: {} bytes", source, output.len()));
+        match source {
+            PipeSource::Cli(pipe_id) => {
+                cli_pipe_output(&pipe_id, &output);
+                unblock_cli_pipe_input(&pipe_id);
+            }
+            PipeSource::Plugin(plugin_id) => pipe_message_to_plugin(
+                MessageToPlugin::new(RESPONSE_PIPE)
+                    .with_destination_plugin_id(plugin_id)
+                    .with_payload(output)
+                    .with_args(self.client_args()),
+            ),
+            PipeSource::Keybind if !self.permission_denied => self.send_pipe(output),
+            PipeSource::Keybind => {}
         }
     }
 
-    len
-}
-
-fn truncate_ansi_string(text: &str, overflow_str: &str, max_len: usize) -> String {
-    let visible_len = calculate_visible_length(text);
-    let overflow_len = overflow_str.len();
-
-    if visible_len <= max_len {
-        return text.to_string();
+    /// The hints as zellij ribbons, for the plugin's own pane.
+    fn components_output(&self) -> String {
+        let mode_hints = collect_hints_for_mode(
+            self.mode_info.mode,
+            &get_keymap_for_mode(&self.mode_info),
+            &self.mode_info.style.colors,
+            &self.render_options,
+            &self.context,
+        );
+        serialize_ribbon_line(hints_to_ribbons(&mode_hints, &self.render_options.keys))
     }
 
-    if max_len <= overflow_len {
-        return overflow_str.to_string();
+    /// Sends the hints of the current mode as a plain sentence to `verbose_pipe_name`, for
+    /// screen readers and the like, whenever the sentence changes.
+    fn send_verbose(&mut self) {
+        let Some(pipe_name) = &self.verbose_pipe_name else {
+            return;
+        };
+        let sentence = describe_hints_for_mode(
+            self.mode_info.mode,
+            &get_keymap_for_mode(&self.mode_info),
+            &self.render_options,
+            &self.context,
+        );
+        if self.last_verbose.as_ref() == Some(&sentence) {
+            return;
+        }
+        pipe_message_to_plugin(
+            MessageToPlugin::new(pipe_name)
+                .with_payload(sentence.clone())
+                .with_args(self.client_args()),
+        );
+        self.last_verbose = Some(sentence);
     }
 
-    let target_len = max_len - overflow_len;
-    let mut result = String::new();
-    let mut visible_count = 0;
-    let mut parser = AnsiParser::new(text);
-
-    while let Some(segment) = parser.next_segment() {
-        match segment {
-            AnsiSegment::EscapeSequence(seq) => {
-                result.push_str(&seq);
+    /// Returns the rendered hints, reusing the previous output when none of its inputs changed.
+    fn cached_output(&mut self) -> String {
+        if self.permission_denied {
+            return PERMISSION_DENIED_MESSAGE.to_string();
+        }
+        if self.show_keymap {
+            return self.keymap_output();
+        }
+        if self.show_pending || self.idle_hidden {
+            return String::new();
+        }
+        let key = self.render_key();
+        if let Some((cached_key, output)) = &self.render_cache {
+            if *cached_key == key {
+                return output.clone();
             }
-            AnsiSegment::VisibleChar(ch) => {
-                if visible_count >= target_len {
-                    break;
-                }
-                result.push(ch);
-                visible_count += 1;
+        }
+        if self.debug {
+            self.debug_log(|| format!("keymap for {:?}: {} entries", key.mode, key.keymap.len()));
+            for (key_with_modifier, actions) in &key.keymap {
+                self.debug_log(|| format!("  {} => {:?}", key_with_modifier, actions));
             }
         }
+        let output = self.render_output(&key, &self.render_options);
+        self.render_cache = Some((key, output.clone()));
+        output
     }
 
-    result.push_str(overflow_str);
-    result
-}
-
-fn find_keys_for_actions(
-    keymap: &[(KeyWithModifier, Vec<Action>)],
-    target_actions: &[Action],
-    exact_match: bool,
-) -> Vec<KeyWithModifier> {
-    keymap
-        .iter()
-        .filter_map(|(key, key_actions)| {
-            if exact_match {
-                let matching = key_actions
-                    .iter()
-                    .zip(target_actions)
-                    .filter(|(a, b)| a.shallow_eq(b))
-                    .count();
-                if matching == key_actions.len() && matching == target_actions.len() {
-                    Some(key.clone())
-                } else {
-                    None
-                }
-            } else {
-                match (key_actions.iter().next(), target_actions.iter().next()) {
-                    (Some(a), Some(b)) if a.shallow_eq(b) => Some(key.clone()),
-                    _ => None,
-                }
-            }
-        })
-        .collect()
-}
+    /// The raw keymap of the current mode in place of the hints, a page of it at a time when it
+    /// doesn't fit `max_length`, or the plugin's pane without one.
+    fn keymap_output(&self) -> String {
+        let mode_hints = keymap_hints(&get_keymap_for_mode(&self.mode_info));
+        let mut line_options = self.line_options.for_mode(self.mode_info.mode);
+        if line_options.max_length == 0 {
+            line_options.max_length = self.cols;
+        }
+        let parts = render_hint_page(
+            mode_hints,
+            self.page,
+            &self.mode_info.style.colors,
+            &self.render_options,
+            &line_options,
+        );
+        format_line(&parts, &line_options)
+    }
 
-fn find_keys_for_action_groups(
-    keymap: &[(KeyWithModifier, Vec<Action>)],
-    action_groups: &[&[Action]],
-) -> Vec<KeyWithModifier> {
-    action_groups
-        .iter()
-        .flat_map(|actions| find_keys_for_actions(keymap, actions, true))
-        .collect()
-}
+    fn render_key(&self) -> RenderCacheKey {
+        RenderCacheKey {
+            mode: self.mode_info.mode,
+            keymap: get_keymap_for_mode(&self.mode_info),
+            colors: self.colors(),
+            context: self.context.clone(),
+            other_client_modes: self.other_client_modes.clone(),
+        }
+    }
 
-fn format_modifier_string(modifiers: &[KeyModifier]) -> String {
-    if modifiers.is_empty() {
-        String::new()
-    } else {
-        modifiers
-            .iter()
-            .map(|m| m.to_string())
-            .collect::<Vec<_>>()
-            .join("-")
+    /// The hints `pipe` carries, given the `output` of the main pipe.
+    fn extra_pipe_output(&self, pipe: &ExtraPipe, output: &str) -> String {
+        let mode = self.mode_info.mode;
+        if output.is_empty()
+            || pipe
+                .modes
+                .as_ref()
+                .is_some_and(|modes| !modes.contains(&mode))
+        {
+            return String::new();
+        }
+        let Some(hints) = &pipe.hints else {
+            return output.to_string();
+        };
+        let mut options = self.render_options.clone();
+        options.layouts.remove(&mode);
+        options.hint_order.insert(mode, hints.clone());
+        self.render_output(&self.render_key(), &options)
     }
-}
 
-fn format_key_display(
-    key_bindings: &[KeyWithModifier],
-    common_modifiers: &[KeyModifier],
-) -> Vec<String> {
-    key_bindings
-        .iter()
-        .map(|key| {
-            if common_modifiers.is_empty() {
-                format!("{}", key)
+    fn render_output(&self, key: &RenderCacheKey, options: &RenderOptions) -> String {
+        if self.hide_in_text_entry && takes_text(key.mode) {
+            return String::new();
+        }
+        let display = self
+            .base_mode_display
+            .in_mode(key.mode, key.context.base_mode, options);
+        // The sync warning shows even when the hints are hidden, on its own
+        let sync_indicator = options
+            .sync_indicator
+            .as_deref()
+            .filter(|_| key.context.sync_active);
+        if display == BaseModeDisplay::Hidden && sync_indicator.is_none() {
+            return String::new();
+        }
+        let line_options = self.line_options.for_mode(key.mode);
+        let hints = || {
+            let mode_hints =
+                collect_hints_for_mode(key.mode, &key.keymap, &key.colors, options, &key.context);
+            if line_options.paging {
+                render_hint_page(mode_hints, self.page, &key.colors, options, &line_options)
             } else {
-                let unique_modifiers = key
-                    .key_modifiers
-                    .iter()
-                    .filter(|m| !common_modifiers.contains(m))
-                    .map(|m| m.to_string())
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                if unique_modifiers.is_empty() {
-                    format!("{}", key.bare_key)
-                } else {
-                    format!("{} {}", unique_modifiers, key.bare_key)
-                }
+                fit_hints(mode_hints, &key.colors, options, &line_options)
             }
-        })
-        .collect()
-}
-
-fn get_key_separator(key_display: &[String]) -> &'static str {
-    let key_string = key_display.join("");
-    if KEY_PATTERNS_NO_SEPARATOR.contains(&&key_string[..]) {
-        ""
-    } else {
-        "|"
+        };
+        let mut parts = base_mode_parts(display, key.mode, hints, sync_indicator, &key.colors);
+        add_other_client_modes(&mut parts, &key.other_client_modes, &key.colors);
+        let mut line = format_line(&parts, &line_options);
+        if let Some(format) = &self.format {
+            line = key.context.expand(format, key.mode, &line);
+        }
+        if self.colors {
+            line
+        } else {
+            strip_ansi(&line)
+        }
     }
-}
 
-fn style_key_with_modifier(
-    key_bindings: &[KeyWithModifier],
-    palette: &Styling,
-) -> Vec<ANSIString<'static>> {
-    if key_bindings.is_empty() {
-        return vec![];
+    /// The `client_id` argument every message carries, once the client is known.
+    fn client_args(&self) -> BTreeMap<String, String> {
+        self.client_id
+            .map(|client_id| ("client_id".to_string(), client_id.to_string()))
+            .into_iter()
+            .collect()
     }
 
-    let saturated_bg = palette_match!(palette.ribbon_unselected.background);
-    let contrasting_fg = palette_match!(palette.ribbon_unselected.base);
-    let mut styled_parts = vec![];
-
-    let common_modifiers = get_common_modifiers(key_bindings.iter().collect());
-    let modifier_str = format_modifier_string(&common_modifiers);
-    let key_display = format_key_display(key_bindings, &common_modifiers);
-    let key_separator = get_key_separator(&key_display);
-
-    styled_parts.push(Style::new().paint(" "));
-
-    if !modifier_str.is_empty() {
-        styled_parts.push(
-            Style::new()
-                .fg(contrasting_fg)
-                .on(saturated_bg)
-                .bold()
-                .paint(format!(" {}-", modifier_str.to_lowercase())),
+    /// Tells the instances running for other clients which mode this client is in.
+    /// Clients back in their base mode send "-" so they drop out of the indicator.
+    fn broadcast_client_mode(&self, mode_info: &ModeInfo) {
+        let Some(client_id) = self.client_id else {
+            return;
+        };
+        let mode = if mode_info.mode == base_mode(mode_info) {
+            "-"
+        } else {
+            mode_name(mode_info.mode)
+        };
+        pipe_message_to_plugin(
+            MessageToPlugin::new(CLIENT_MODE_PIPE).with_payload(format!("{}:{}", client_id, mode)),
         );
-    } else {
-        styled_parts.push(Style::new().fg(contrasting_fg).on(saturated_bg).paint(" "));
     }
 
-    for (idx, key) in key_display.iter().enumerate() {
-        if idx > 0 && !key_separator.is_empty() {
-            styled_parts.push(
-                Style::new()
-                    .fg(contrasting_fg)
-                    .on(saturated_bg)
-                    .paint(key_separator),
-            );
+    /// Records a mode broadcast from another client.
+    fn receive_client_mode(&mut self, payload: &str) {
+        let Some((client_id, mode)) = payload.split_once(':') else {
+            return;
+        };
+        let Ok(client_id) = client_id.parse::<ClientId>() else {
+            return;
+        };
+        if Some(client_id) == self.client_id {
+            return;
         }
-        styled_parts.push(
-            Style::new()
-                .fg(contrasting_fg)
-                .on(saturated_bg)
-                .bold()
-                .paint(key.clone()),
-        );
+        match parse_mode(mode) {
+            Some(mode) => self.other_client_modes.insert(client_id, mode),
+            None => self.other_client_modes.remove(&client_id),
+        };
     }
 
-    styled_parts.push(Style::new().fg(contrasting_fg).on(saturated_bg).paint(" "));
-
-    styled_parts
-}
-
-fn style_description(description: &str, palette: &Styling, keys: &[KeyWithModifier]) -> Vec<ANSIString<'static>> {
-    let less_saturated_bg = palette_match!(palette.text_unselected.background);
-    let contrasting_fg = palette_match!(palette.text_unselected.base);
-    let highlight_fg = palette_match!(palette.ribbon_selected.base);
-
-    let mut parts = vec![];
-
-    parts.push(Style::new()
-        .fg(contrasting_fg)
-        .on(less_saturated_bg)
-        .paint(" "));
-
-    if !description.is_empty() {
-        // Get the key letter to highlight
-        let key_char = if !keys.is_empty() {
-            format!("{}", keys[0].bare_key).to_lowercase().chars().next()
-        } else {
-            None
-        };
+    /// Writes a diagnostic line to `debug_file`, or to the zellij log when no file is set.
+    fn debug_log(&self, message: impl FnOnce() -> String) {
+        if !self.debug {
+            return;
+        }
+        let line = format!("zjstatus-hints: {}", message());
+        let written = self.debug_file.as_ref().is_some_and(|path| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", line))
+                .is_ok()
+        });
+        if !written {
+            eprintln!("{}", line);
+        }
+    }
 
-        // Find the position of the key letter in the description
-        let highlight_pos = if let Some(kc) = key_char {
-            description.to_lowercase().find(kc)
-        } else {
-            Some(0) // Default to first character if no key
+    /// Shows the hints once the user has stayed in a modal mode for `show_delay`, ends their
+    /// flash after `flash_duration`, and hides them again after `auto_hide_after`, setting a timer
+    /// for whichever is due next. Timers are shared with pipe throttling, so one that fires early
+    /// just waits for the rest.
+    fn check_mode_timers(&mut self) {
+        let Some(entered) = self.mode_entered else {
+            return;
         };
-
-        if let Some(pos) = highlight_pos {
-            // Split description into parts: before, highlighted char, after
-            let chars: Vec<char> = description.chars().collect();
-
-            if pos > 0 {
-                let before: &'static str = Box::leak(
-                    chars[..pos].iter().collect::<String>().into_boxed_str()
-                );
-                parts.push(Style::new()
-                    .fg(contrasting_fg)
-                    .on(less_saturated_bg)
-                    .paint(before));
+        let elapsed = entered.elapsed();
+        let mut next_due: Option<Duration> = None;
+        if self.show_pending {
+            if elapsed >= self.show_delay {
+                self.show_pending = false;
+            } else {
+                next_due = Some(self.show_delay - elapsed);
             }
-
-            let highlighted: &'static str = Box::leak(
-                chars[pos].to_string().into_boxed_str()
-            );
-            parts.push(Style::new()
-                .fg(highlight_fg)
-                .on(less_saturated_bg)
-                .bold()
-                .paint(highlighted));
-
-            if pos + 1 < chars.len() {
-                let after: &'static str = Box::leak(
-                    chars[pos + 1..].iter().collect::<String>().into_boxed_str()
-                );
-                parts.push(Style::new()
-                    .fg(contrasting_fg)
-                    .on(less_saturated_bg)
-                    .paint(after));
+        }
+        if self.flashing {
+            if elapsed >= self.flash_duration {
+                self.flashing = false;
+            } else {
+                let due = self.flash_duration - elapsed;
+                next_due = Some(next_due.map_or(due, |next| next.min(due)));
             }
-        } else {
-            // Key letter not in description, just render description normally
-            let text: &'static str = Box::leak(description.to_string().into_boxed_str());
-            parts.push(Style::new()
-                .fg(contrasting_fg)
-                .on(less_saturated_bg)
-                .paint(text));
         }
-    }
-
-    parts.push(Style::new()
-        .fg(contrasting_fg)
-        .on(less_saturated_bg)
-        .paint(" "));
-
-    parts
-}
-
-fn plugin_key(
-    keymap: &[(KeyWithModifier, Vec<Action>)],
-    plugin_name: &str,
-) -> Option<KeyWithModifier> {
-    keymap.iter().find_map(|(key, key_actions)| {
-        if key_actions
-            .iter()
-            .any(|action| action.launches_plugin(plugin_name))
-        {
-            Some(key.clone())
-        } else {
-            None
+        if !self.auto_hide_after.is_zero() && !self.idle_hidden {
+            if elapsed >= self.auto_hide_after {
+                self.idle_hidden = true;
+            } else {
+                let due = self.auto_hide_after - elapsed;
+                next_due = Some(next_due.map_or(due, |next| next.min(due)));
+            }
+        }
+        if let Some(due) = next_due {
+            set_timeout(due.as_secs_f64());
         }
-    })
-}
-
-fn get_select_key(keymap: &[(KeyWithModifier, Vec<Action>)]) -> Vec<KeyWithModifier> {
-    let to_normal_keys = find_keys_for_actions(keymap, &[TO_NORMAL], true);
-    if to_normal_keys.contains(&KeyWithModifier::new(BareKey::Enter)) {
-        vec![KeyWithModifier::new(BareKey::Enter)]
-    } else {
-        to_normal_keys.into_iter().take(1).collect()
     }
-}
 
-fn add_hint(
-    parts: &mut Vec<ANSIString<'static>>,
-    keys: &[KeyWithModifier],
-    description: &str,
-    colors: &Styling,
-) {
-    if !keys.is_empty() {
-        let styled_keys = style_key_with_modifier(keys, colors);
-        parts.extend(styled_keys);
-        let styled_desc = style_description(description, colors, keys);
-        parts.extend(styled_desc);
+    fn update_context(&mut self) {
+        let simplified_ui = self.context.simplified_ui;
+        let last_mode = self.context.last_mode;
+        let previous_mode = self.context.previous_mode;
+        self.context = HintContext::new(self.context.base_mode, &self.tabs, &self.panes);
+        self.context.simplified_ui = simplified_ui;
+        self.context.last_mode = last_mode;
+        self.context.previous_mode = previous_mode;
     }
-}
-
-fn add_description_only(
-    parts: &mut Vec<ANSIString<'static>>,
-    description: &str,
-    colors: &Styling,
-) {
-    let styled_desc = style_description(description, colors, &[]);
-    parts.extend(styled_desc);
-}
 
-fn render_hints_for_mode(
-    mode: InputMode,
-    keymap: &[(KeyWithModifier, Vec<Action>)],
-    colors: &Styling,
-) -> Vec<ANSIString<'static>> {
-    let mut parts = vec![];
-    let select_keys = get_select_key(keymap);
-
-    match mode {
-        InputMode::Normal => {
-            // Collect actions that have keybindings
-            let actions_with_keys: Vec<(Action, &'static str, Vec<KeyWithModifier>)> = NORMAL_MODE_ACTIONS
-                .iter()
-                .filter_map(|(action, label)| {
-                    let keys = find_keys_for_actions(keymap, &[action.clone()], true);
-                    if !keys.is_empty() {
-                        Some((action.clone(), *label, keys))
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            if !actions_with_keys.is_empty() {
-                let all_keys: Vec<KeyWithModifier> = actions_with_keys
-                    .iter()
-                    .flat_map(|(_, _, keys)| keys.clone())
-                    .collect();
-
-                let common_modifiers = get_common_modifiers(all_keys.iter().collect());
-
-                if !common_modifiers.is_empty() {
-                    let saturated_bg = palette_match!(colors.ribbon_unselected.background);
-                    let contrasting_fg = palette_match!(colors.ribbon_unselected.base);
-                    let modifier_str = format_modifier_string(&common_modifiers);
-
-                    parts.push(Style::new().paint(" "));
-                    parts.push(
-                        Style::new()
-                            .fg(contrasting_fg)
-                            .on(saturated_bg)
-                            .bold()
-                            .paint(format!(" {} ", modifier_str.to_lowercase())),
-                    );
-                }
+    /// Scrolls the hints in the plugin's pane sideways, no further than it takes to bring their
+    /// end into view. Returns whether they moved.
+    fn scroll(&mut self, mouse: Mouse) -> bool {
+        let width = self
+            .last_output
+            .as_deref()
+            .map_or(0, calculate_visible_length);
+        let offset = match mouse {
+            Mouse::ScrollDown(lines) => self.scroll_offset + lines * SCROLL_COLUMNS,
+            Mouse::ScrollUp(lines) => self.scroll_offset.saturating_sub(lines * SCROLL_COLUMNS),
+            _ => return false,
+        };
+        let offset = offset.min(width.saturating_sub(self.cols));
+        let moved = offset != self.scroll_offset;
+        self.scroll_offset = offset;
+        moved
+    }
 
-                // Add labels only for actions with keybindings
-                for (_, label, _) in actions_with_keys {
-                    add_description_only(&mut parts, label, colors);
-                }
-            }
+    /// Applies a line received on `OVERRIDE_PIPE`: `hint <mode> <label>=<actions>` adds a hint
+    /// as `<mode>_hint_<label>` would, `label <mode> <hint>=<label>` relabels the hints a name
+    /// matches, and `clear` drops every override.
+    fn apply_override(&mut self, line: &str) -> Result<(), String> {
+        let line = line.trim();
+        if line == "clear" {
+            self.render_options.custom_hints = self.configured_custom_hints.clone();
+            self.render_options.relabels.clear();
+            return Ok(());
         }
-        InputMode::Pane => {
-            for (actions, label) in PANE_MODE_ACTION_SEQUENCES {
-                let keys = find_keys_for_actions(keymap, actions, false);
-                if !keys.is_empty() {
-                    add_hint(&mut parts, &keys, label, colors);
+        let mut words = line.splitn(3, ' ');
+        let (Some(command), Some(mode), Some(assignment)) =
+            (words.next(), words.next(), words.next())
+        else {
+            return Err(format!(
+                "expected <command> <mode> <name>=<value>, got {:?}",
+                line
+            ));
+        };
+        let mode = parse_mode(mode).ok_or_else(|| format!("unknown mode {:?}", mode))?;
+        let (name, value) = assignment
+            .split_once('=')
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .ok_or_else(|| format!("expected <name>=<value>, got {:?}", assignment))?;
+        match command {
+            "hint" => {
+                let actions = parse_action_patterns(value)?;
+                if actions.is_empty() {
+                    return Err(format!("hint {:?} needs an action", name));
                 }
+                let label = name.replace('_', " ");
+                let hints = self.render_options.custom_hints.entry(mode).or_default();
+                hints.retain(|hint| hint.label != label);
+                hints.push(CustomHint {
+                    label,
+                    actions,
+                    matching: ActionMatch::default(),
+                    style: HintStyle::default(),
+                });
             }
-
-            let rename_keys = find_keys_for_actions(
-                keymap,
-                &[
-                    Action::SwitchToMode(InputMode::RenamePane),
-                    Action::PaneNameInput(vec![0]),
-                ],
-                false,
-            );
-            if !rename_keys.is_empty() {
-                add_hint(&mut parts, &rename_keys, "rename", colors);
+            "label" => {
+                let relabels = self.render_options.relabels.entry(mode).or_default();
+                relabels.retain(|(hint, _)| hint != name);
+                relabels.insert(0, (name.to_string(), value.to_string()));
             }
+            _ => return Err(format!("unknown override {:?}", command)),
+        }
+        Ok(())
+    }
 
-            let focus_keys = find_keys_for_action_groups(
-                keymap,
-                &[
-                    &[Action::MoveFocus(Direction::Left)],
-                    &[Action::MoveFocus(Direction::Down)],
-                    &[Action::MoveFocus(Direction::Up)],
-                    &[Action::MoveFocus(Direction::Right)],
-                ],
-            );
-            add_hint(&mut parts, &focus_keys, "move", colors);
-            add_hint(&mut parts, &select_keys, "select", colors);
-        }
-        InputMode::Tab => {
-            for (actions, label) in TAB_MODE_ACTION_SEQUENCES {
-                let keys = find_keys_for_actions(keymap, actions, false);
-                if !keys.is_empty() {
-                    add_hint(&mut parts, &keys, label, colors);
-                }
+    /// Runs a command received on `COMMAND_PIPE`: `set <option>=<value>`, `next_page`,
+    /// `prev_page`, `toggle_keymap` or `dump-config [<file>]`.
+    fn run_command(&mut self, command: &str, source: &PipeSource) {
+        let result = match command.trim().split_once(' ') {
+            None if command.trim() == "dump-config" => {
+                self.dump_config(None, source);
+                Ok(())
             }
-
-            let rename_keys = find_keys_for_actions(
-                keymap,
-                &[
-                    Action::SwitchToMode(InputMode::RenameTab),
-                    Action::TabNameInput(vec![0]),
-                ],
-                false,
-            );
-            if !rename_keys.is_empty() {
-                add_hint(&mut parts, &rename_keys, "rename", colors);
+            Some(("dump-config", path)) => {
+                self.dump_config(Some(path.trim()), source);
+                Ok(())
             }
-
-            let focus_keys_full = find_keys_for_action_groups(
-                keymap,
-                &[&[Action::GoToPreviousTab], &[Action::GoToNextTab]],
-            );
-            let focus_keys = if focus_keys_full.contains(&KeyWithModifier::new(BareKey::Left))
-                && focus_keys_full.contains(&KeyWithModifier::new(BareKey::Right))
-            {
-                vec![
-                    KeyWithModifier::new(BareKey::Left),
-                    KeyWithModifier::new(BareKey::Right),
-                ]
-            } else {
-                focus_keys_full
-            };
-            add_hint(&mut parts, &focus_keys, "move", colors);
-            add_hint(&mut parts, &select_keys, "select", colors);
-        }
-        InputMode::Resize => {
-            let resize_keys = find_keys_for_action_groups(
-                keymap,
-                &[
-                    &[Action::Resize(Resize::Increase, None)],
-                    &[Action::Resize(Resize::Decrease, None)],
-                ],
-            );
-            add_hint(&mut parts, &resize_keys, "resize", colors);
-
-            let increase_keys = find_keys_for_action_groups(
-                keymap,
-                &[
-                    &[Action::Resize(Resize::Increase, Some(Direction::Left))],
-                    &[Action::Resize(Resize::Increase, Some(Direction::Down))],
-                    &[Action::Resize(Resize::Increase, Some(Direction::Up))],
-                    &[Action::Resize(Resize::Increase, Some(Direction::Right))],
-                ],
-            );
-            add_hint(&mut parts, &increase_keys, "increase", colors);
-
-            let decrease_keys = find_keys_for_action_groups(
-                keymap,
-                &[
-                    &[Action::Resize(Resize::Decrease, Some(Direction::Left))],
-                    &[Action::Resize(Resize::Decrease, Some(Direction::Down))],
-                    &[Action::Resize(Resize::Decrease, Some(Direction::Up))],
-                    &[Action::Resize(Resize::Decrease, Some(Direction::Right))],
-                ],
-            );
-            add_hint(&mut parts, &decrease_keys, "decrease", colors);
-            add_hint(&mut parts, &select_keys, "select", colors);
-        }
-        InputMode::Move => {
-            let move_keys = find_keys_for_action_groups(
-                keymap,
-                &[
-                    &[Action::MovePane(Some(Direction::Left))],
-                    &[Action::MovePane(Some(Direction::Down))],
-                    &[Action::MovePane(Some(Direction::Up))],
-                    &[Action::MovePane(Some(Direction::Right))],
-                ],
-            );
-            add_hint(&mut parts, &move_keys, "move", colors);
-            add_hint(&mut parts, &select_keys, "select", colors);
-        }
-        InputMode::Scroll => {
-            let search_keys = find_keys_for_actions(
-                keymap,
-                &[
-                    Action::SwitchToMode(InputMode::EnterSearch),
-                    Action::SearchInput(vec![0]),
-                ],
-                true,
-            );
-            add_hint(&mut parts, &search_keys, "search", colors);
-
-            let scroll_keys =
-                find_keys_for_action_groups(keymap, &[&[Action::ScrollDown], &[Action::ScrollUp]]);
-            add_hint(&mut parts, &scroll_keys, "scroll", colors);
-
-            let page_scroll_keys = find_keys_for_action_groups(
-                keymap,
-                &[&[Action::PageScrollDown], &[Action::PageScrollUp]],
-            );
-            add_hint(&mut parts, &page_scroll_keys, "page", colors);
-
-            let half_page_scroll_keys = find_keys_for_action_groups(
-                keymap,
-                &[&[Action::HalfPageScrollDown], &[Action::HalfPageScrollUp]],
-            );
-            add_hint(&mut parts, &half_page_scroll_keys, "half page", colors);
-
-            let edit_keys =
-                find_keys_for_actions(keymap, &[Action::EditScrollback, TO_NORMAL], false);
-            if !edit_keys.is_empty() {
-                add_hint(&mut parts, &edit_keys, "edit", colors);
+            None if command.trim() == "toggle_keymap" => {
+                self.show_keymap = !self.show_keymap;
+                self.page = 0;
+                Ok(())
             }
-            add_hint(&mut parts, &select_keys, "select", colors);
-        }
-        InputMode::Search => {
-            let search_keys = find_keys_for_actions(
-                keymap,
-                &[
-                    Action::SwitchToMode(InputMode::EnterSearch),
-                    Action::SearchInput(vec![0]),
-                ],
-                true,
-            );
-            add_hint(&mut parts, &search_keys, "search", colors);
-
-            let scroll_keys =
-                find_keys_for_action_groups(keymap, &[&[Action::ScrollDown], &[Action::ScrollUp]]);
-            add_hint(&mut parts, &scroll_keys, "scroll", colors);
-
-            let page_scroll_keys = find_keys_for_action_groups(
-                keymap,
-                &[&[Action::PageScrollDown], &[Action::PageScrollUp]],
-            );
-            add_hint(&mut parts, &page_scroll_keys, "page", colors);
-
-            let half_page_scroll_keys = find_keys_for_action_groups(
-                keymap,
-                &[&[Action::HalfPageScrollDown], &[Action::HalfPageScrollUp]],
-            );
-            add_hint(&mut parts, &half_page_scroll_keys, "half page", colors);
-
-            let down_keys =
-                find_keys_for_actions(keymap, &[Action::Search(SearchDirection::Down)], true);
-            add_hint(&mut parts, &down_keys, "down", colors);
-
-            let up_keys =
-                find_keys_for_actions(keymap, &[Action::Search(SearchDirection::Up)], true);
-            add_hint(&mut parts, &up_keys, "up", colors);
-
-            add_hint(&mut parts, &select_keys, "select", colors);
-        }
-        InputMode::Session => {
-            let detach_keys = find_keys_for_actions(keymap, &[Action::Detach], true);
-            add_hint(&mut parts, &detach_keys, "detach", colors);
-
-            if let Some(manager_key) = plugin_key(keymap, PLUGIN_SESSION_MANAGER) {
-                add_hint(&mut parts, &[manager_key], "manager", colors);
+            None if command.trim() == "next_page" => {
+                self.page += 1;
+                Ok(())
             }
-
-            if let Some(config_key) = plugin_key(keymap, PLUGIN_CONFIGURATION) {
-                add_hint(&mut parts, &[config_key], "config", colors);
+            None if command.trim() == "prev_page" => {
+                self.page -= 1;
+                Ok(())
             }
+            Some(("set", assignment)) => match assignment.split_once('=') {
+                Some((option, value)) => self.set_option(option.trim(), value.trim()),
+                None => Err(format!("expected <option>=<value>, got {:?}", assignment)),
+            },
+            _ => Err(format!("unknown command {:?}", command)),
+        };
+        if let Err(error) = result {
+            eprintln!("zjstatus-hints: {}", error);
+        }
+        // The cached output was rendered with the previous options
+        self.render_cache = None;
+    }
 
-            if let Some(plugin_key_val) = plugin_key(keymap, PLUGIN_MANAGER) {
-                add_hint(&mut parts, &[plugin_key_val], "plugins", colors);
+    /// Lists every option with its kind, default and current value, see `dump_config`: into
+    /// `path` (e.g. under `/host`) when given, otherwise as the output of a CLI pipe or into the
+    /// zellij log.
+    fn dump_config(&self, path: Option<&str>, source: &PipeSource) {
+        let dump = dump_config(&self.configuration);
+        if let Some(path) = path {
+            if let Err(error) = std::fs::write(path, &dump) {
+                eprintln!("zjstatus-hints: dump-config: {}: {}", path, error);
             }
+        } else if let PipeSource::Cli(pipe_id) = source {
+            cli_pipe_output(pipe_id, &dump);
+        } else {
+            eprint!("{}", dump);
+        }
+        if let PipeSource::Cli(pipe_id) = source {
+            unblock_cli_pipe_input(pipe_id);
+        }
+    }
 
-            if let Some(about_key) = plugin_key(keymap, PLUGIN_ABOUT) {
-                add_hint(&mut parts, &[about_key], "about", colors);
+    fn set_option(&mut self, option: &str, value: &str) -> Result<(), String> {
+        match option {
+            "max_length" => {
+                self.line_options.max_length = value
+                    .parse()
+                    .map_err(|_| format!("invalid max_length: {:?}", value))?;
             }
-
-            add_hint(&mut parts, &select_keys, "select", colors);
+            "theme" if value.is_empty() => self.theme = None,
+            "theme" if self.themes.contains_key(value) => self.theme = Some(value.to_string()),
+            "theme" => return Err(format!("no theme_{} configured", value)),
+            _ => return Err(format!("option {:?} can't be set at runtime", option)),
         }
-        _ => {
-            let keys =
-                find_keys_for_actions(keymap, &[Action::SwitchToMode(InputMode::Normal)], true);
-            add_hint(&mut parts, &keys, "normal", colors);
+        self.configuration
+            .insert(option.to_string(), value.to_string());
+        Ok(())
+    }
+
+    /// Zellij's theme, with the colors of the selected `theme_<name>` on top.
+    fn colors(&self) -> Styling {
+        let colors = self.mode_info.style.colors;
+        let mut colors = match self.theme.as_ref().and_then(|name| self.themes.get(name)) {
+            Some(theme) => theme.apply(&colors),
+            None => colors,
+        };
+        // Flashing swaps the key pills' colors with those of the mode badge and the highlights
+        if self.flashing {
+            std::mem::swap(&mut colors.ribbon_unselected, &mut colors.ribbon_selected);
         }
+        colors
     }
 
-    parts
+    /// Whether the output would differ from what was last rendered and piped.
+    fn output_changed(&mut self) -> bool {
+        let output = self.cached_output();
+        self.last_output.as_ref() != Some(&output)
+    }
+}
+
+/// The mode the user returns to after an action. Zellij versions that don't report it always
+/// return to Normal mode.
+fn base_mode(mode_info: &ModeInfo) -> InputMode {
+    mode_info.base_mode.unwrap_or(InputMode::Normal)
+}
+
+/// The ids of the plugin panes running zjstatus, going by their URL, e.g.
+/// "file:~/.config/zellij/plugins/zjstatus.wasm". This plugin's own name contains it too.
+fn zjstatus_panes(panes: &PaneManifest) -> Vec<u32> {
+    panes
+        .panes
+        .values()
+        .flatten()
+        .filter(|pane| pane.is_plugin)
+        .filter(|pane| {
+            pane.plugin_url
+                .as_deref()
+                .is_some_and(|url| url.contains("zjstatus") && !url.contains("zjstatus-hints"))
+        })
+        .map(|pane| pane.id)
+        .collect()
 }
 
-fn get_keymap_for_mode(mode_info: &ModeInfo) -> Vec<(KeyWithModifier, Vec<Action>)> {
-    match mode_info.mode {
-        InputMode::Normal => mode_info.get_keybinds_for_mode(InputMode::Normal),
-        InputMode::Pane => mode_info.get_keybinds_for_mode(InputMode::Pane),
-        InputMode::Tab => mode_info.get_keybinds_for_mode(InputMode::Tab),
-        InputMode::Resize => mode_info.get_keybinds_for_mode(InputMode::Resize),
-        InputMode::Move => mode_info.get_keybinds_for_mode(InputMode::Move),
-        InputMode::Scroll => mode_info.get_keybinds_for_mode(InputMode::Scroll),
-        InputMode::Search => mode_info.get_keybinds_for_mode(InputMode::Search),
-        InputMode::Session => mode_info.get_keybinds_for_mode(InputMode::Session),
-        _ => mode_info.get_mode_keybinds(),
+/// A one-line summary of an event for the debug log.
+fn describe_event(event: &Event) -> String {
+    match event {
+        Event::ModeUpdate(mode_info) => format!(
+            "ModeUpdate mode={:?} base_mode={:?}",
+            mode_info.mode, mode_info.base_mode
+        ),
+        Event::TabUpdate(tabs) => format!("TabUpdate tabs={}", tabs.len()),
+        Event::PaneUpdate(panes) => format!("PaneUpdate tabs={}", panes.panes.len()),
+        Event::SessionUpdate(sessions, _) => format!("SessionUpdate sessions={}", sessions.len()),
+        Event::Timer(elapsed) => format!("Timer elapsed={}", elapsed),
+        other => format!("{:?}", other),
     }
 }